@@ -0,0 +1,145 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::Write;
+use crate::println;
+
+/// Amount of bytes printed per line by `print_to_logs`.
+const BYTES_PER_LOG_LINE: usize = 64;
+
+/**
+ * Prints the bytes as hex to the logs, between START_<marker> and STOP_<marker> lines.
+ * This is the only way to get data out of a real Wii.
+ * bytes_from_logs.py (in the python_support folder) turns this back into a file.
+ */
+pub fn print_to_logs(marker: &str, bytes: &[u8]) {
+    println!("START_{}", marker);
+    for chunk in bytes.chunks(BYTES_PER_LOG_LINE) {
+        let mut line = String::with_capacity(chunk.len() * 2);
+        for byte in chunk {
+            let _ = write!(line, "{:02x}", byte);
+        }
+        println!("H_{}", line);
+    }
+    println!("STOP_{}", marker);
+}
+
+/**
+ * Small helper to build compact binary logs, for example for replays.
+ * Integers are written as LEB128 varints, floats as little endian bytes.
+ */
+#[derive(Debug, Default)]
+pub struct ByteWriter {
+    bytes: Vec<u8>,
+}
+
+impl ByteWriter {
+    pub fn new() -> ByteWriter {
+        ByteWriter { bytes: Vec::new() }
+    }
+
+    pub fn write_u8(&mut self, value: u8) {
+        self.bytes.push(value);
+    }
+
+    pub fn write_bytes(&mut self, values: &[u8]) {
+        self.bytes.extend_from_slice(values);
+    }
+
+    pub fn write_varint(&mut self, mut value: u64) {
+        loop {
+            let byte = (value & 0x7F) as u8;
+            value >>= 7;
+            if value == 0 {
+                self.bytes.push(byte);
+                return;
+            }
+            self.bytes.push(byte | 0x80);
+        }
+    }
+
+    pub fn write_f32(&mut self, value: f32) {
+        self.bytes.extend_from_slice(&value.to_le_bytes());
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.bytes
+    }
+}
+
+/**
+ * Reads back what was written by a `ByteWriter`.
+ * Every read returns `None` once the bytes run out (or a varint is malformed).
+ */
+pub struct ByteReader<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl<'a> ByteReader<'a> {
+    pub fn new(bytes: &'a [u8]) -> ByteReader<'a> {
+        ByteReader { bytes, position: 0 }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.position >= self.bytes.len()
+    }
+
+    pub fn read_u8(&mut self) -> Option<u8> {
+        let value = *self.bytes.get(self.position)?;
+        self.position += 1;
+        Some(value)
+    }
+
+    pub fn read_bytes(&mut self, len: usize) -> Option<&'a [u8]> {
+        let end = self.position.checked_add(len)?;
+        let values = self.bytes.get(self.position..end)?;
+        self.position = end;
+        Some(values)
+    }
+
+    pub fn read_varint(&mut self) -> Option<u64> {
+        let mut value: u64 = 0;
+        for shift in (0..64).step_by(7) {
+            let byte = self.read_u8()?;
+            value |= ((byte & 0x7F) as u64) << shift;
+            if byte & 0x80 == 0 {
+                return Some(value);
+            }
+        }
+        None
+    }
+
+    pub fn read_f32(&mut self) -> Option<f32> {
+        let bytes = self.read_bytes(4)?;
+        Some(f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ByteReader, ByteWriter};
+
+    #[test]
+    fn test_round_trip() {
+        let mut writer = ByteWriter::new();
+        writer.write_u8(7);
+        writer.write_varint(0);
+        writer.write_varint(300);
+        writer.write_varint(u64::MAX);
+        writer.write_f32(-1.5);
+
+        let bytes = writer.into_bytes();
+        let mut reader = ByteReader::new(&bytes);
+        assert_eq!(reader.read_u8(), Some(7));
+        assert_eq!(reader.read_varint(), Some(0));
+        assert_eq!(reader.read_varint(), Some(300));
+        assert_eq!(reader.read_varint(), Some(u64::MAX));
+        assert_eq!(reader.read_f32(), Some(-1.5));
+        assert!(reader.is_empty());
+        assert_eq!(reader.read_u8(), None);
+    }
+}
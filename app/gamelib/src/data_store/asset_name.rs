@@ -1,8 +1,10 @@
+use strum_macros::{EnumIter, EnumString};
+
 /**
  * Enumerates all models that exist in the project.
  * Each of them can be turned into its actual raw data by calling `to_data()` on it.
  */
-#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone)]
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, EnumIter, EnumString)]
 pub enum AssetName {
     Cube,
     Suzanne,
@@ -37,6 +39,14 @@ pub enum AssetName {
     OilBubbleTexture,
     OilSeaTexture,
     SweetPotatoTexture,
+    GoldenPotatoTexture,
+    HudFont,
+    BouncingCubesScene,
+    MatchScene,
+    MovingPlatformScene,
+    PauseScene,
+    PotatoScene,
+    ResultsScene,
 }
 
 impl AssetName {
@@ -84,6 +94,14 @@ impl AssetName {
             AssetName::OilBubbleTexture => include_bytes!("data/OilBubble.png"),
             AssetName::OilSeaTexture => include_bytes!("data/OilSea.png"),
             AssetName::SweetPotatoTexture => include_bytes!("data/SweetPotato.png"),
+            AssetName::GoldenPotatoTexture => include_bytes!("data/GoldenPotato.png"),
+            AssetName::HudFont => include_bytes!("data/HudFont.png"),
+            AssetName::BouncingCubesScene => include_bytes!("data/scenes/bouncing_cubes.scene"),
+            AssetName::MatchScene => include_bytes!("data/scenes/match.scene"),
+            AssetName::MovingPlatformScene => include_bytes!("data/scenes/moving_platform.scene"),
+            AssetName::PauseScene => include_bytes!("data/scenes/pause.scene"),
+            AssetName::PotatoScene => include_bytes!("data/scenes/potato.scene"),
+            AssetName::ResultsScene => include_bytes!("data/scenes/results.scene"),
         }
     }
 }
@@ -1,11 +1,11 @@
 use crate::data_store::asset_name::AssetName;
-use strum_macros::EnumIter;
+use strum_macros::{EnumIter, EnumString};
 
 /**
  * Enumerates all textured models that exist in the project.
  * Each of them can be turned into its associated assets.
  */
-#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, EnumIter)]
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, EnumIter, EnumString)]
 pub enum TexturedModelName {
     Cube,
     Triangle,
@@ -24,6 +24,7 @@ pub enum TexturedModelName {
     OilBubble,
     OilSea,
     SweetPotato,
+    GoldenPotato,
 }
 
 impl TexturedModelName {
@@ -47,6 +48,7 @@ impl TexturedModelName {
             TexturedModelName::OilBubble => (AssetName::OilBubble, AssetName::OilBubbleTexture),
             TexturedModelName::OilSea => (AssetName::OilSea, AssetName::OilSeaTexture),
             TexturedModelName::SweetPotato => (AssetName::SweetPotato, AssetName::SweetPotatoTexture),
+            TexturedModelName::GoldenPotato => (AssetName::Potato, AssetName::GoldenPotatoTexture),
         }
     }
 }
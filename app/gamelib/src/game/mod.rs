@@ -3,69 +3,591 @@ use alloc::rc::Rc;
 #[cfg(not(feature = "wii"))]
 use std::rc::Rc;
 use core::cell::RefCell;
-use crate::game_state::{changes::ChangeProvider, GameState};
-use crate::game_states::{GameStateName, GameStateFactory};
+use core::time::Duration;
+use hashbrown::HashMap;
+use rand::rngs::SmallRng;
+use rand::{RngCore, SeedableRng};
+#[cfg(feature = "wii")]
+use ogc_rs::prelude::Vec;
+#[cfg(feature = "wii")]
+use ogc_rs::prelude::vec;
+#[cfg(not(feature = "wii"))]
+use std::vec::Vec;
+#[cfg(not(feature = "wii"))]
+use std::vec;
+use crate::bytes::print_to_logs;
+use crate::game_state::{changes::{ChangeProvider, Changes}, snapshot, GameState};
+use crate::println;
+use crate::game_states::{GameStateName, GameStateFactory, Transition};
 use crate::servers::ServerProvider;
 
+/// Time simulated by a single simulation step (and physics world step).
+pub const FIXED_TIMESTEP: Duration = Duration::from_nanos(1_000_000_000 / 30);
+
+/// Maximum number of simulation steps to catch up with in a single rendered frame.
+/// When the game falls behind further than this, the remaining time is dropped (the game slows down),
+/// rather than spending ever more time simulating.
+pub const MAX_STEPS_PER_FRAME: u32 = 4;
+
 /**
  * Main game loop struct that should handle the game flow, with help from
  * provided change provider and renderer. 
+ *
+ * The game keeps a stack of states. The one on top gets the controls,
+ * the ones below are frozen unless the states above them let them update (`GameState::updates_below`),
+ * and are only drawn when the states above them are overlays (`GameState::is_overlay`).
  */
 pub struct Game<C> {
-    state: GameState,
+    /// All states with their names, the current one last.
+    stack: Vec<(GameStateName, GameState)>,
+    /// The snapshot of every state as it was last left, so it can be resumed.
+    snapshots: HashMap<GameStateName, Vec<u8>>,
     change_provider: C,
-    server_provider: Rc<RefCell<ServerProvider>>   
+    server_provider: Rc<RefCell<ServerProvider>>,
+    /// Seeds every state that is built, so each match plays out differently, but the same for the same game seed.
+    rng: SmallRng,
+    /// Time that has passed, but has not been simulated yet.
+    accumulator: Duration,
+    /// Changes of frames in which no simulation step was done yet.
+    pending_changes: Option<Changes>,
+    steps_last_frame: u32,
 }
 
 impl<C: ChangeProvider> Game<C> {
     /**
      * Construct a new game with the initial game state 
      * and wii specific change provider + renderer.
+     * All randomness in the game is drawn from the given seed.
      */
     pub fn new(
         start_state: GameStateName, 
+        seed: u64,
         change_provider: C, 
         server_provider: ServerProvider
     ) -> Self {
-        let mut state = GameStateFactory::to_state(start_state);
         let server_ref = Rc::new(RefCell::new(server_provider));
+        let mut rng = SmallRng::seed_from_u64(seed);
+        let mut state = GameStateFactory::to_state(start_state.clone(), rng.next_u64());
         state.server_provider = Some(server_ref.clone());
         let res = Self {
-            state,
+            stack: vec![(start_state, state)],
+            snapshots: HashMap::new(),
             change_provider,
-            server_provider: server_ref
+            server_provider: server_ref,
+            rng,
+            accumulator: Duration::ZERO,
+            pending_changes: None,
+            steps_last_frame: 0,
         };
         res
     }
 
+    /**
+     * The change provider the game gets its input from,
+     * for example to get the log out of a `RecordingChangeProvider`.
+     */
+    pub fn change_provider(&self) -> &C {
+        &self.change_provider
+    }
+
+    /// The current state, on top of the stack.
+    pub fn state(&self) -> &GameState {
+        &self.stack.last().unwrap().1
+    }
+
+    fn state_mut(&mut self) -> &mut GameState {
+        &mut self.stack.last_mut().unwrap().1
+    }
+
+    /// Number of states on the stack.
+    pub fn stack_depth(&self) -> usize {
+        self.stack.len()
+    }
+
+    /**
+     * Serializes the current state, c.f. `snapshot::save`.
+     */
+    pub fn snapshot(&self) -> Vec<u8> {
+        snapshot::save(self.state())
+    }
+
+    /**
+     * Prints a snapshot of the current state to the logs, between START_SNAPSHOT and STOP_SNAPSHOT markers.
+     * Attach it to a bug report, so the state can be restored on the host.
+     */
+    pub fn snapshot_to_logs(&self) {
+        print_to_logs("SNAPSHOT", &self.snapshot());
+    }
+
+    /// Number of simulation steps that were done during the last rendered frame.
+    pub fn steps_last_frame(&self) -> u32 {
+        self.steps_last_frame
+    }
+
     /**
      * When called this method performs one update to the whole game,
      * which is one rendered frame.
+     *
+     * The simulation (including the physics world) runs in steps of `FIXED_TIMESTEP`, as many as fit in the time that passed.
+     * Rendering happens once, in between the last step and the next one.
      */
     pub fn update(&mut self) -> bool {
-        // Get the changes and update the current state.
+        // Get the changes, and add them to those no simulation step has seen yet.
         let changes = self.change_provider.get_changes();
-        let is_running = self.state.update(changes);
-        if ! is_running {
-            return false;
-        }
-
-        // We are still running, so do the rest.
-        let mut server_provider = self.server_provider.as_ref().borrow_mut();
-        server_provider.render_server.world_step();
-        server_provider.render_server.render_frame();
-
-        match &self.state.next_state {
-            Some(next_state) => {
-                // TODO : this is where you'd save the current scene, if you wanted that support.
-                let mut new_state = GameStateFactory::to_state(next_state.clone());
-                new_state.server_provider = Some(self.server_provider.clone());
-                self.state = new_state;
-                // TODO: this is where you'd load a potential earlier save of the newly load scene, if you wanted that support.
+        self.accumulator += changes.delta_time;
+        let mut step_changes = match self.pending_changes.take() {
+            Some(mut pending) => {
+                pending.merge(changes);
+                pending
             },
-            None => ()
+            None => changes
+        };
+
+        self.steps_last_frame = 0;
+        let top = self.stack.len() - 1;
+        let first_active = self.first_active_index();
+        while self.accumulator >= FIXED_TIMESTEP && self.steps_last_frame < MAX_STEPS_PER_FRAME {
+            self.accumulator -= FIXED_TIMESTEP;
+            self.steps_last_frame += 1;
+            let next_step_changes = step_changes.repeated();
+            step_changes.delta_time = FIXED_TIMESTEP;
+
+            // Only the state on top gets the controls, the ones below it just keep going.
+            for (_name, state) in self.stack[first_active..top].iter_mut() {
+                let mut background_changes = Changes::nothing();
+                background_changes.delta_time = FIXED_TIMESTEP;
+                state.simulate(background_changes);
+            }
+            self.stack[top].1.simulate(step_changes);
+            if self.stack[first_active..].iter().any(|(_name, state)| state.uses_physics()) {
+                self.server_provider.as_ref().borrow_mut().physics_server.world_step();
+            }
+            for (_name, state) in self.stack[first_active..].iter_mut() {
+                if ! state.sync_physics() {
+                    return false;
+                }
+            }
+
+            step_changes = next_step_changes;
+            if self.stack.iter().any(|(_name, state)| state.transition.is_some()) {
+                break;
+            }
+        }
+        if self.steps_last_frame == 0 {
+            self.pending_changes = Some(step_changes);
+        }
+        if self.accumulator >= FIXED_TIMESTEP {
+            // Too far behind to catch up, only keep the part of a step we are into.
+            let step_nanos = FIXED_TIMESTEP.as_nanos() as u64;
+            self.accumulator = Duration::from_nanos(self.accumulator.as_nanos() as u64 % step_nanos);
+        }
+
+        // Render what we have, in between the last step and the next one.
+        // Frozen states are drawn as they were after their last step.
+        let interpolation = self.accumulator.as_secs_f32() / FIXED_TIMESTEP.as_secs_f32();
+        let first_visible = self.first_visible_index();
+        for (index, (_name, state)) in self.stack.iter_mut().enumerate().skip(first_visible) {
+            state.interpolation = if index >= first_active { interpolation } else { 1.0 };
+            state.render();
+        }
+        self.server_provider.as_ref().borrow_mut().render_server.render_frame();
+        for (_name, state) in self.stack.iter_mut() {
+            state.end_frame();
+        }
+
+        // The transition of the highest state wins.
+        let mut transition = None;
+        for (index, (_name, state)) in self.stack.iter_mut().enumerate().rev() {
+            let state_transition = state.transition.take();
+            if transition.is_none() {
+                transition = state_transition.map(|state_transition| (index, state_transition));
+            }
+        }
+        if let Some((index, transition)) = transition {
+            self.apply_transition(index, transition);
         }
 
         return true;
     }
+
+    /// Index of the lowest state on the stack that still runs its simulation.
+    fn first_active_index(&self) -> usize {
+        let mut index = self.stack.len() - 1;
+        while index > 0 && self.stack[index].1.updates_below {
+            index -= 1;
+        }
+        index
+    }
+
+    /// Index of the lowest state on the stack that is still drawn.
+    fn first_visible_index(&self) -> usize {
+        let mut index = self.stack.len() - 1;
+        while index > 0 && self.stack[index].1.is_overlay {
+            index -= 1;
+        }
+        index
+    }
+
+    /**
+     * Changes the stack of states, on behalf of the state at the given index.
+     * The states above it are left first, so a transition always applies to the state that asked for it.
+     * Every state that is left and can be resumed is remembered as it was, so it can be resumed later on.
+     * The results of a match are handed on from the state that asked for the transition to the state on top after it.
+     */
+    fn apply_transition(&mut self, index: usize, transition: Transition) {
+        let results = self.stack[index].1.results.take();
+        match transition {
+            Transition::Push(name) => {
+                self.leave_states_from(index + 1);
+                let state = self.build_state(&name, false);
+                self.stack.push((name, state));
+            },
+            Transition::Pop => {
+                if index > 0 {
+                    self.leave_states_from(index);
+                } else {
+                    println!("Cannot pop the last state");
+                }
+            },
+            Transition::Replace(name) => {
+                self.leave_states_from(index);
+                let state = self.build_state(&name, false);
+                self.stack.push((name, state));
+            },
+            Transition::Resume(name) => {
+                self.leave_states_from(index);
+                let state = self.build_state(&name, true);
+                self.stack.push((name, state));
+            },
+        }
+        if results.is_some() {
+            self.state_mut().results = results;
+        }
+    }
+
+    /// Removes the state at the given index and all states above it, and remembers how the ones that can be resumed were left.
+    fn leave_states_from(&mut self, index: usize) {
+        while self.stack.len() > index {
+            if let Some((name, state)) = self.stack.pop() {
+                if name.can_resume() {
+                    self.snapshots.insert(name, snapshot::save(&state));
+                }
+            }
+        }
+    }
+
+    /**
+     * Builds the given state. When resuming, it continues where it was left the last time (if it was played before).
+     */
+    fn build_state(&mut self, name: &GameStateName, resume: bool) -> GameState {
+        let seed = self.rng.next_u64();
+        let mut state = GameStateFactory::to_state(name.clone(), seed);
+        state.server_provider = Some(self.server_provider.clone());
+        if resume {
+            if let Some(bytes) = self.snapshots.get(name) {
+                if let Err(error) = snapshot::restore(&mut state, bytes) {
+                    println!("Could not resume {:?}, starting it fresh: {:?}", name, error);
+                    state = GameStateFactory::to_state(name.clone(), seed);
+                    state.server_provider = Some(self.server_provider.clone());
+                }
+            }
+        }
+        state
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::time::Duration;
+    use strum::IntoEnumIterator;
+    use crate::data_store::asset_name::AssetName;
+    use crate::data_store::textured_model_name::TexturedModelName;
+    use crate::game_state::changes::{ChangeProvider, Changes};
+    use crate::game_state::changes::controls::{Direction, MotionControl};
+    use crate::game_state::changes::replay::{RecordingChangeProvider, ReplayChangeProvider};
+    use crate::game_state::PlayMode as GamePlayMode;
+    use crate::game_state::components::game::{PanColour, PotatoKind};
+    use crate::game_state::components::physics::SphereCollider;
+    use crate::game_states::GameStateName;
+    use crate::servers::audio::PlayMode;
+    use crate::servers::recording::{recording_server_provider, RecordedAudio, RecordedFrame, RecordedMesh};
+    use super::{Game, MAX_STEPS_PER_FRAME};
+
+    /// The potatoes of all kinds that were drawn in the frame.
+    fn potatoes(frame: &RecordedFrame) -> Vec<RecordedMesh> {
+        frame.meshes.iter()
+            .filter(|mesh| PotatoKind::iter().any(|kind| kind.model_name() == mesh.model_name))
+            .cloned()
+            .collect()
+    }
+
+    /// Provides no input at all, at a steady frame rate.
+    struct SteadyChangeProvider {
+        delta_time: Duration,
+    }
+
+    impl ChangeProvider for SteadyChangeProvider {
+        fn get_changes(&mut self) -> Changes {
+            let mut changes = Changes::nothing();
+            changes.delta_time = self.delta_time;
+            changes
+        }
+    }
+
+    /// Shakes the first controller every now and then, at a slightly unsteady frame rate.
+    struct ShakingChangeProvider {
+        frame: u32,
+    }
+
+    impl ChangeProvider for ShakingChangeProvider {
+        fn get_changes(&mut self) -> Changes {
+            self.frame += 1;
+            let mut changes = Changes::nothing();
+            changes.delta_time = Duration::from_micros(16_000 + (self.frame % 7) as u64 * 100);
+            if self.frame % 20 == 0 {
+                changes.controls.wii_mote_controls[0].motion = Some(MotionControl {
+                    direction: Direction::Xp,
+                    started: true,
+                    ended: false,
+                });
+            }
+            changes
+        }
+    }
+
+    /// Joins the match with the first controller and gets ready, on top of the changes of another change provider.
+    struct JoiningChangeProvider<C> {
+        inner: C,
+        frame: u32,
+    }
+
+    impl<C> JoiningChangeProvider<C> {
+        fn new(inner: C) -> Self {
+            Self { inner, frame: 0 }
+        }
+    }
+
+    impl<C: ChangeProvider> ChangeProvider for JoiningChangeProvider<C> {
+        fn get_changes(&mut self) -> Changes {
+            self.frame += 1;
+            let mut changes = self.inner.get_changes();
+            changes.controls.wii_mote_controls[0].a_button_down |= self.frame == 1 || self.frame == 5;
+            changes
+        }
+    }
+
+    /// Presses 'plus' on the first controller on the given frames, at a steady 60 FPS.
+    struct PlusChangeProvider {
+        frame: u32,
+        press_on: [u32; 2],
+    }
+
+    impl ChangeProvider for PlusChangeProvider {
+        fn get_changes(&mut self) -> Changes {
+            self.frame += 1;
+            let mut changes = Changes::nothing();
+            changes.delta_time = Duration::from_micros(16_667);
+            changes.controls.wii_mote_controls[0].plus_button_down = self.press_on.contains(&self.frame);
+            changes
+        }
+    }
+
+    #[test]
+    fn test_bouncing_cubes_headless() {
+        let (server_provider, recording) = recording_server_provider();
+        let change_provider = JoiningChangeProvider::new(SteadyChangeProvider { delta_time: Duration::from_micros(16_667) });
+        let mut game = Game::new(GameStateName::BouncingCubes, 10, change_provider, server_provider);
+
+        // Five seconds at 60 FPS is enough to get through the countdown.
+        for _ in 0..300 {
+            assert!(game.update());
+        }
+
+        let recording = recording.borrow();
+        assert_eq!(recording.frames().len(), 300);
+        assert!(recording.frames().iter().all(|frame| frame.cameras.len() == 1));
+
+        // The music is started exactly once.
+        let music_plays = recording.audio_calls()
+            .filter(|call| **call == RecordedAudio::Play(AssetName::DemoMusic, PlayMode::Infinite))
+            .count();
+        assert_eq!(music_plays, 1);
+
+        // Before the countdown there are no potatoes, after it all potatoes and the frying pans of the player and the bots are on screen.
+        let first_frame = &recording.frames()[0];
+        assert_eq!(potatoes(first_frame).len(), 0);
+        let last_frame = recording.frames().last().unwrap();
+        assert_eq!(potatoes(last_frame).len(), 20);
+        assert_eq!(last_frame.count_meshes(&TexturedModelName::FryPanBlack), 1);
+        assert_eq!(last_frame.count_meshes(&TexturedModelName::FryPanRed), 1);
+        assert_eq!(last_frame.count_meshes(&TexturedModelName::OilBubble), 20);
+        // The sun, the sky and the glow of the oil light up the meshes.
+        assert_eq!(last_frame.lights.len(), 3);
+        // Steam rises from the oil.
+        assert!(!last_frame.billboards.is_empty());
+
+        // The HUD only shows up once the match is being played, with a score panel for every fry pan.
+        assert!(first_frame.texts.is_empty());
+        assert!(last_frame.has_text("TARGET 21"));
+        assert_eq!(last_frame.texts.len(), 5);
+        // Every score is drawn in the colour of its fry pan.
+        for pan_colour in PanColour::iter() {
+            let score = last_frame.texts.iter().find(|text| text.colour == pan_colour.colour()).unwrap();
+            assert!(score.text.parse::<usize>().is_ok());
+        }
+        assert_eq!(last_frame.quads.len(), 8);
+    }
+
+    #[test]
+    fn test_fixed_timestep() {
+        let (server_provider, _recording) = recording_server_provider();
+        let change_provider = SteadyChangeProvider { delta_time: Duration::from_micros(16_667) };
+        let mut game = Game::new(GameStateName::BouncingCubes, 10, change_provider, server_provider);
+
+        // One second at 60 FPS is simulated in 30 steps, independent of the frame rate.
+        let mut steps = 0;
+        for _ in 0..60 {
+            assert!(game.update());
+            steps += game.steps_last_frame();
+            assert!((0.0..=1.0).contains(&game.state().interpolation));
+        }
+        assert_eq!(steps, 30);
+
+        // After a long hiccup only a limited amount of steps is caught up on.
+        game.change_provider.delta_time = Duration::from_secs(1);
+        assert!(game.update());
+        assert_eq!(game.steps_last_frame(), MAX_STEPS_PER_FRAME);
+        game.change_provider.delta_time = Duration::from_micros(16_667);
+        assert!(game.update());
+        assert!(game.steps_last_frame() <= 1);
+    }
+
+    #[test]
+    fn test_resume_state() {
+        let (server_provider, _recording) = recording_server_provider();
+        let change_provider = JoiningChangeProvider::new(SteadyChangeProvider { delta_time: Duration::from_micros(16_667) });
+        let mut game = Game::new(GameStateName::BouncingCubes, 10, change_provider, server_provider);
+        for _ in 0..300 {
+            assert!(game.update());
+        }
+        assert_eq!(game.state().playmode, GamePlayMode::Playing);
+
+        // Leave the match for another state, and come back to it.
+        game.state_mut().replace_state(GameStateName::MovingPlatform);
+        assert!(game.update());
+        assert_eq!(game.state().world.query::<&SphereCollider>().iter().count(), 0);
+        game.state_mut().resume_state(GameStateName::BouncingCubes);
+        assert!(game.update());
+        assert_eq!(game.state().playmode, GamePlayMode::Playing);
+        assert_eq!(game.state().world.query::<&SphereCollider>().iter().count(), 20);
+        for _ in 0..10 {
+            assert!(game.update());
+        }
+
+        // Without resuming, the match starts all over.
+        game.state_mut().replace_state(GameStateName::BouncingCubes);
+        assert!(game.update());
+        assert_eq!(game.state().playmode, GamePlayMode::Selection);
+    }
+
+    #[test]
+    fn test_pause_overlay() {
+        let (server_provider, recording) = recording_server_provider();
+        let change_provider = JoiningChangeProvider::new(PlusChangeProvider { frame: 0, press_on: [310, 410] });
+        let mut game = Game::new(GameStateName::BouncingCubes, 10, change_provider, server_provider);
+        for _ in 0..311 {
+            assert!(game.update());
+        }
+        assert_eq!(game.stack_depth(), 2);
+
+        // While paused, the match is drawn underneath the pause state, without moving.
+        for _ in 0..99 {
+            assert!(game.update());
+        }
+        {
+            let recording = recording.borrow();
+            let paused_frames = &recording.frames()[312..410];
+            for frame in paused_frames {
+                assert_eq!(potatoes(frame).len(), 20);
+                assert_eq!(frame.count_meshes(&TexturedModelName::HandHolding), 1);
+                assert_eq!(potatoes(frame), potatoes(&paused_frames[0]));
+            }
+        }
+
+        // Pressing 'plus' again continues the match.
+        for _ in 0..2 {
+            assert!(game.update());
+        }
+        assert_eq!(game.stack_depth(), 1);
+        assert_eq!(game.state().playmode, GamePlayMode::Playing);
+        let recording = recording.borrow();
+        let last_frame = recording.frames().last().unwrap();
+        assert_eq!(potatoes(last_frame).len(), 20);
+        assert_eq!(last_frame.count_meshes(&TexturedModelName::HandHolding), 0);
+    }
+
+    #[test]
+    fn test_transition_of_state_below_overlay() {
+        let (server_provider, _recording) = recording_server_provider();
+        let change_provider = SteadyChangeProvider { delta_time: Duration::from_micros(16_667) };
+        let mut game = Game::new(GameStateName::BouncingCubes, 10, change_provider, server_provider);
+        game.state_mut().push_state(GameStateName::Pause);
+        assert!(game.update());
+        assert_eq!(game.stack_depth(), 2);
+
+        // The state below the overlay keeps running, and replaces itself: the overlay goes along with it.
+        game.stack[1].1.updates_below = true;
+        game.stack[0].1.replace_state(GameStateName::MovingPlatform);
+        assert!(game.update());
+        assert_eq!(game.stack_depth(), 1);
+        assert_eq!(game.stack[0].0, GameStateName::MovingPlatform);
+
+        // Only states that can be resumed are remembered.
+        assert!(game.snapshots.contains_key(&GameStateName::BouncingCubes));
+        assert!(!game.snapshots.contains_key(&GameStateName::Pause));
+    }
+
+    #[test]
+    fn test_seed_varies_matches() {
+        let potatoes_after_countdown = |seed: u64| -> Vec<RecordedMesh> {
+            let (server_provider, recording) = recording_server_provider();
+            let change_provider = JoiningChangeProvider::new(SteadyChangeProvider { delta_time: Duration::from_micros(16_667) });
+            let mut game = Game::new(GameStateName::BouncingCubes, seed, change_provider, server_provider);
+            for _ in 0..300 {
+                assert!(game.update());
+            }
+            let recording = recording.borrow();
+            let last_frame = recording.frames().last().unwrap();
+            potatoes(last_frame)
+        };
+        assert_eq!(potatoes_after_countdown(1), potatoes_after_countdown(1));
+        assert_ne!(potatoes_after_countdown(1), potatoes_after_countdown(2));
+    }
+
+    #[test]
+    fn test_replay_reproduces_game() {
+        let frames = 400;
+        let (server_provider, recording) = recording_server_provider();
+        let change_provider = RecordingChangeProvider::new(JoiningChangeProvider::new(ShakingChangeProvider { frame: 0 }), 10);
+        let mut game = Game::new(GameStateName::BouncingCubes, 10, change_provider, server_provider);
+        for _ in 0..frames {
+            assert!(game.update());
+        }
+        let log = game.change_provider().log().to_vec();
+
+        let (replay_server_provider, replay_recording) = recording_server_provider();
+        let replay = ReplayChangeProvider::new(&log).unwrap();
+        let mut replay_game = Game::new(GameStateName::BouncingCubes, replay.seed(), replay, replay_server_provider);
+        for _ in 0..frames {
+            assert!(replay_game.update());
+        }
+        assert!(replay_game.change_provider().is_finished());
+
+        // Every frame is drawn exactly the same.
+        let recording = recording.borrow();
+        let replay_recording = replay_recording.borrow();
+        let original: Vec<&Vec<RecordedMesh>> = recording.frames().iter().map(|frame| &frame.meshes).collect();
+        let replayed: Vec<&Vec<RecordedMesh>> = replay_recording.frames().iter().map(|frame| &frame.meshes).collect();
+        assert_eq!(original, replayed);
+    }
 }
@@ -8,11 +8,14 @@ use ogc_rs::prelude::vec;
 use alloc::vec::Vec;
 #[cfg(not(feature = "wii"))]
 use alloc::vec;
+/// The number of Wii Remotes that can be connected, and so the number of `Controls::wii_mote_controls`.
+pub const WII_MOTE_COUNT: usize = 4;
+
 /// Represents the state of one or multiple Wii controllers w.r.t. the game
 ///
 /// Its fields are public so they can be filled in from outside the library
 /// (before `GameState::update` is called)
-
+#[derive(Debug, Clone)]
 pub struct Controls {
     pub wii_mote_controls: Vec<WiiMoteControl>
 }
@@ -24,15 +27,51 @@ impl Controls {
         }
     }
 
-    pub fn get_wii_mote_control(&self, controller_assignment: &ControllerAssignment) -> &WiiMoteControl {
-        return &self.wii_mote_controls[controller_assignment.id]
+    /**
+     * The controls of the Wii Remote behind an assignment, or None for controllers that are not a Wii Remote (like bots).
+     */
+    pub fn get_wii_mote_control(&self, controller_assignment: &ControllerAssignment) -> Option<&WiiMoteControl> {
+        return self.wii_mote_controls.get(controller_assignment.id)
+    }
+
+    /**
+     * Folds the controls of a later frame into these,
+     * so nothing is missed when no simulation step ran in between.
+     */
+    pub fn merge(&mut self, later: Controls) {
+        for (control, later_control) in self.wii_mote_controls.iter_mut().zip(later.wii_mote_controls) {
+            control.merge(later_control);
+        }
+    }
+
+    /**
+     * The controls for another simulation step within the same frame:
+     * button presses and started motions are only seen by the first step.
+     */
+    pub fn repeated(&self) -> Controls {
+        let mut controls = self.clone();
+        for control in controls.wii_mote_controls.iter_mut() {
+            control.home_button_down = false;
+            control.one_button_down = false;
+            control.two_button_down = false;
+            control.plus_button_down = false;
+            control.a_button_down = false;
+            if let Some(motion) = control.motion.as_mut() {
+                motion.started = false;
+            }
+        }
+        controls
     }
 }
 
+#[derive(Debug, Clone)]
 pub struct WiiMoteControl {
     pub motion: Option<MotionControl>,
     pub home_button_down: bool,
     pub one_button_down: bool,
+    pub two_button_down: bool,
+    pub plus_button_down: bool,
+    pub a_button_down: bool,
 }
 
 impl WiiMoteControl {
@@ -41,10 +80,31 @@ impl WiiMoteControl {
             motion: None,
             home_button_down: false,
             one_button_down: false,
+            two_button_down: false,
+            plus_button_down: false,
+            a_button_down: false,
         }
     }
+
+    fn merge(&mut self, later: WiiMoteControl) {
+        self.home_button_down |= later.home_button_down;
+        self.one_button_down |= later.one_button_down;
+        self.two_button_down |= later.two_button_down;
+        self.plus_button_down |= later.plus_button_down;
+        self.a_button_down |= later.a_button_down;
+        self.motion = match (self.motion.take(), later.motion) {
+            // Keep the start of the motion, otherwise it would never be acted upon.
+            (Some(mut earlier), Some(later)) if earlier.started => {
+                earlier.ended |= later.ended;
+                Some(earlier)
+            }
+            (earlier, None) => earlier,
+            (_, later) => later,
+        };
+    }
 }
 
+#[derive(Debug, Clone)]
 pub struct MotionControl {
     pub direction: Direction,
     pub started: bool,
@@ -1,4 +1,5 @@
 pub mod controls;
+pub mod replay;
 
 use core::time::Duration;
 use controls::Controls;
@@ -7,6 +8,7 @@ use controls::Controls;
 ///
 /// Its fields are public so they can be filled in from outside the library
 /// (before `GameState::update` is called)
+#[derive(Debug, Clone)]
 pub struct Changes {
     pub controls: Controls,
     pub delta_time: Duration,
@@ -19,6 +21,24 @@ impl Changes {
             delta_time: Duration::new(0, 0)
         };
     }
+
+    /**
+     * Folds the changes of a later frame into these.
+     */
+    pub fn merge(&mut self, later: Changes) {
+        self.controls.merge(later.controls);
+        self.delta_time += later.delta_time;
+    }
+
+    /**
+     * The changes for another simulation step within the same frame.
+     */
+    pub fn repeated(&self) -> Changes {
+        Changes {
+            controls: self.controls.repeated(),
+            delta_time: self.delta_time,
+        }
+    }
 }
 
 /**
@@ -0,0 +1,349 @@
+use alloc::vec::Vec;
+use core::time::Duration;
+use crate::bytes::{print_to_logs, ByteReader, ByteWriter};
+use crate::println;
+use super::controls::{Controls, Direction, MotionControl, WiiMoteControl};
+use super::{ChangeProvider, Changes};
+
+/// Every replay log starts with these bytes, followed by the format version.
+const REPLAY_MAGIC: &[u8; 4] = b"GGJR";
+const REPLAY_VERSION: u8 = 2;
+
+const FLAG_HOME_BUTTON: u8 = 1 << 0;
+const FLAG_ONE_BUTTON: u8 = 1 << 1;
+const FLAG_MOTION: u8 = 1 << 2;
+const FLAG_MOTION_STARTED: u8 = 1 << 3;
+const FLAG_MOTION_ENDED: u8 = 1 << 4;
+const FLAG_TWO_BUTTON: u8 = 1 << 5;
+const FLAG_PLUS_BUTTON: u8 = 1 << 6;
+const FLAG_A_BUTTON: u8 = 1 << 7;
+
+/// How large a replay log can grow by default, which is about half an hour of play.
+/// After that, recording stops, so a long session does not eat up the memory of the Wii.
+pub const MAX_REPLAY_BYTES: usize = 1 << 20;
+
+/**
+ * Reasons why a replay log could not be read.
+ */
+#[derive(Debug, PartialEq)]
+pub enum ReplayError {
+    /// The log does not start with the replay header.
+    NotAReplay,
+    /// The log was written by an unknown version of the format.
+    UnsupportedVersion(u8),
+    /// The log ends halfway through a frame.
+    Truncated { frame: usize },
+    /// The log contains something that is not a valid frame.
+    Corrupt { frame: usize },
+}
+
+/**
+ * Change provider that passes on the changes of another change provider,
+ * while writing every frame of them into a compact binary log.
+ * The seed the game was started with is written in front of the frames, so the replay draws the same random numbers.
+ *
+ * The log can be fed back into the game with a `ReplayChangeProvider`.
+ * Once the log is full, the frames after it are no longer recorded; the log then replays the start of the session.
+ */
+pub struct RecordingChangeProvider<C> {
+    inner: C,
+    log: ByteWriter,
+    frame_count: usize,
+    max_bytes: usize,
+    is_full: bool,
+}
+
+impl<C: ChangeProvider> RecordingChangeProvider<C> {
+    pub fn new(inner: C, seed: u64) -> Self {
+        Self::with_limit(inner, seed, MAX_REPLAY_BYTES)
+    }
+
+    /// Records at most `max_bytes` of log, including the header.
+    pub fn with_limit(inner: C, seed: u64, max_bytes: usize) -> Self {
+        let mut log = ByteWriter::new();
+        log.write_bytes(REPLAY_MAGIC);
+        log.write_u8(REPLAY_VERSION);
+        log.write_varint(seed);
+        Self {
+            inner,
+            log,
+            frame_count: 0,
+            max_bytes,
+            is_full: false,
+        }
+    }
+
+    /// Whether recording stopped because the log reached its limit.
+    pub fn is_full(&self) -> bool {
+        self.is_full
+    }
+
+    /// The log of all frames recorded so far.
+    pub fn log(&self) -> &[u8] {
+        self.log.as_bytes()
+    }
+
+    pub fn frame_count(&self) -> usize {
+        self.frame_count
+    }
+
+    /**
+     * Prints the log to the logs, between START_REPLAY and STOP_REPLAY markers.
+     */
+    pub fn to_logs(&self) {
+        if self.is_full {
+            println!("Replay log is full, only the first {} frames were recorded", self.frame_count);
+        }
+        print_to_logs("REPLAY", self.log());
+    }
+}
+
+impl<C: ChangeProvider> ChangeProvider for RecordingChangeProvider<C> {
+    fn get_changes(&mut self) -> Changes {
+        let changes = self.inner.get_changes();
+        if self.is_full {
+            return changes;
+        }
+        let mut frame = ByteWriter::new();
+        write_changes(&mut frame, &changes);
+        if self.log.as_bytes().len() + frame.as_bytes().len() > self.max_bytes {
+            self.is_full = true;
+            return changes;
+        }
+        self.log.write_bytes(frame.as_bytes());
+        self.frame_count += 1;
+        changes
+    }
+}
+
+/**
+ * Change provider that plays back a log made by a `RecordingChangeProvider`, frame by frame.
+ * Once all frames are played back, it provides no changes at all.
+ */
+pub struct ReplayChangeProvider {
+    seed: u64,
+    /// Frames still to be played back, the next one last.
+    frames: Vec<Changes>,
+}
+
+impl ReplayChangeProvider {
+    pub fn new(log: &[u8]) -> Result<ReplayChangeProvider, ReplayError> {
+        let mut reader = ByteReader::new(log);
+        if reader.read_bytes(REPLAY_MAGIC.len()) != Some(&REPLAY_MAGIC[..]) {
+            return Err(ReplayError::NotAReplay);
+        }
+        match reader.read_u8() {
+            Some(REPLAY_VERSION) => (),
+            Some(version) => return Err(ReplayError::UnsupportedVersion(version)),
+            None => return Err(ReplayError::NotAReplay),
+        }
+        let seed = reader.read_varint().ok_or(ReplayError::NotAReplay)?;
+
+        let mut frames = Vec::new();
+        while !reader.is_empty() {
+            frames.push(read_changes(&mut reader, frames.len())?);
+        }
+        frames.reverse();
+        Ok(ReplayChangeProvider { seed, frames })
+    }
+
+    /// The seed the recorded game was started with, to start the replayed game with.
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Number of frames that are still to be played back.
+    pub fn frames_left(&self) -> usize {
+        self.frames.len()
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.frames.is_empty()
+    }
+}
+
+impl ChangeProvider for ReplayChangeProvider {
+    fn get_changes(&mut self) -> Changes {
+        self.frames.pop().unwrap_or_else(Changes::nothing)
+    }
+}
+
+fn write_changes(log: &mut ByteWriter, changes: &Changes) {
+    log.write_varint(changes.delta_time.as_nanos() as u64);
+    let wii_mote_controls = &changes.controls.wii_mote_controls;
+    log.write_u8(wii_mote_controls.len() as u8);
+    for control in wii_mote_controls {
+        let mut flags = 0;
+        if control.home_button_down {
+            flags |= FLAG_HOME_BUTTON;
+        }
+        if control.one_button_down {
+            flags |= FLAG_ONE_BUTTON;
+        }
+        if control.two_button_down {
+            flags |= FLAG_TWO_BUTTON;
+        }
+        if control.plus_button_down {
+            flags |= FLAG_PLUS_BUTTON;
+        }
+        if control.a_button_down {
+            flags |= FLAG_A_BUTTON;
+        }
+        if let Some(motion) = &control.motion {
+            flags |= FLAG_MOTION;
+            if motion.started {
+                flags |= FLAG_MOTION_STARTED;
+            }
+            if motion.ended {
+                flags |= FLAG_MOTION_ENDED;
+            }
+        }
+        log.write_u8(flags);
+        if let Some(motion) = &control.motion {
+            log.write_u8(direction_to_byte(motion.direction));
+        }
+    }
+}
+
+fn read_changes(reader: &mut ByteReader, frame: usize) -> Result<Changes, ReplayError> {
+    let delta_nanos = reader.read_varint().ok_or(ReplayError::Truncated { frame })?;
+    let controller_count = reader.read_u8().ok_or(ReplayError::Truncated { frame })?;
+
+    let mut wii_mote_controls = Vec::with_capacity(controller_count as usize);
+    for _ in 0..controller_count {
+        let flags = reader.read_u8().ok_or(ReplayError::Truncated { frame })?;
+        let motion = if flags & FLAG_MOTION != 0 {
+            let byte = reader.read_u8().ok_or(ReplayError::Truncated { frame })?;
+            Some(MotionControl {
+                direction: direction_from_byte(byte).ok_or(ReplayError::Corrupt { frame })?,
+                started: flags & FLAG_MOTION_STARTED != 0,
+                ended: flags & FLAG_MOTION_ENDED != 0,
+            })
+        } else {
+            None
+        };
+        wii_mote_controls.push(WiiMoteControl {
+            motion,
+            home_button_down: flags & FLAG_HOME_BUTTON != 0,
+            one_button_down: flags & FLAG_ONE_BUTTON != 0,
+            two_button_down: flags & FLAG_TWO_BUTTON != 0,
+            plus_button_down: flags & FLAG_PLUS_BUTTON != 0,
+            a_button_down: flags & FLAG_A_BUTTON != 0,
+        });
+    }
+
+    Ok(Changes {
+        controls: Controls { wii_mote_controls },
+        delta_time: Duration::from_nanos(delta_nanos),
+    })
+}
+
+fn direction_to_byte(direction: Direction) -> u8 {
+    match direction {
+        Direction::Xp => 0,
+        Direction::Xn => 1,
+        Direction::Yp => 2,
+        Direction::Yn => 3,
+        Direction::Zp => 4,
+        Direction::Zn => 5,
+    }
+}
+
+fn direction_from_byte(byte: u8) -> Option<Direction> {
+    match byte {
+        0 => Some(Direction::Xp),
+        1 => Some(Direction::Xn),
+        2 => Some(Direction::Yp),
+        3 => Some(Direction::Yn),
+        4 => Some(Direction::Zp),
+        5 => Some(Direction::Zn),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::time::Duration;
+    use crate::game_state::changes::controls::{Direction, MotionControl};
+    use crate::game_state::changes::{ChangeProvider, Changes};
+    use super::{RecordingChangeProvider, ReplayChangeProvider, ReplayError};
+
+    /// Shakes the second controller every third frame, with a varying frame time.
+    struct ShakingChangeProvider {
+        frame: u64,
+    }
+
+    impl ChangeProvider for ShakingChangeProvider {
+        fn get_changes(&mut self) -> Changes {
+            self.frame += 1;
+            let mut changes = Changes::nothing();
+            changes.delta_time = Duration::from_nanos(16_000_000 + self.frame * 1_000);
+            changes.controls.wii_mote_controls[0].one_button_down = self.frame % 5 == 0;
+            if self.frame % 3 == 0 {
+                changes.controls.wii_mote_controls[1].motion = Some(MotionControl {
+                    direction: Direction::Zn,
+                    started: true,
+                    ended: false,
+                });
+            }
+            changes
+        }
+    }
+
+    #[test]
+    fn test_replay_round_trip() {
+        let mut recorder = RecordingChangeProvider::new(ShakingChangeProvider { frame: 0 }, 1234);
+        let recorded: Vec<Changes> = (0..100).map(|_| recorder.get_changes()).collect();
+        assert_eq!(recorder.frame_count(), 100);
+
+        let mut replay = ReplayChangeProvider::new(recorder.log()).unwrap();
+        assert_eq!(replay.seed(), 1234);
+        assert_eq!(replay.frames_left(), 100);
+        for expected in recorded.iter() {
+            let changes = replay.get_changes();
+            assert_eq!(changes.delta_time, expected.delta_time);
+            for (control, expected_control) in changes.controls.wii_mote_controls.iter()
+                .zip(expected.controls.wii_mote_controls.iter()) {
+                assert_eq!(control.one_button_down, expected_control.one_button_down);
+                assert_eq!(control.home_button_down, expected_control.home_button_down);
+                assert_eq!(control.two_button_down, expected_control.two_button_down);
+                assert_eq!(control.plus_button_down, expected_control.plus_button_down);
+                assert_eq!(control.a_button_down, expected_control.a_button_down);
+                assert_eq!(
+                    control.motion.as_ref().map(|motion| (motion.direction, motion.started, motion.ended)),
+                    expected_control.motion.as_ref().map(|motion| (motion.direction, motion.started, motion.ended))
+                );
+            }
+        }
+        assert!(replay.is_finished());
+        assert_eq!(replay.get_changes().delta_time, Duration::ZERO);
+    }
+
+    #[test]
+    fn test_recording_stops_when_full() {
+        let mut recorder = RecordingChangeProvider::with_limit(ShakingChangeProvider { frame: 0 }, 1234, 64);
+        for _ in 0..100 {
+            recorder.get_changes();
+        }
+        assert!(recorder.is_full());
+        assert!(recorder.log().len() <= 64);
+        assert!(recorder.frame_count() < 100);
+
+        // What was recorded is still a valid replay of the first frames.
+        let replay = ReplayChangeProvider::new(recorder.log()).unwrap();
+        assert_eq!(replay.frames_left(), recorder.frame_count());
+    }
+
+    #[test]
+    fn test_replay_rejects_broken_logs() {
+        assert!(matches!(ReplayChangeProvider::new(b"nope"), Err(ReplayError::NotAReplay)));
+
+        let mut recorder = RecordingChangeProvider::new(ShakingChangeProvider { frame: 0 }, 0);
+        recorder.get_changes();
+        let log = recorder.log();
+        assert_eq!(
+            ReplayChangeProvider::new(&log[..log.len() - 1]).err(),
+            Some(ReplayError::Truncated { frame: 0 })
+        );
+    }
+}
@@ -4,11 +4,13 @@ use crate::{data_store::asset_name::AssetName, servers::audio::PlayMode};
 pub struct Audio {
     pub asset_name: AssetName,
     pub play_mode: PlayMode,
-    pub is_processed: bool
+    pub is_processed: bool,
+    /// From 0 (silent) to 255, 100 being the usual volume.
+    pub volume: f32,
 }
 
 impl Audio {
     pub fn new(asset_name: AssetName, play_mode: PlayMode) -> Self {
-        Self { asset_name, play_mode, is_processed: false }
+        Self { asset_name, play_mode, is_processed: false, volume: 100.0 }
     }
 }
\ No newline at end of file
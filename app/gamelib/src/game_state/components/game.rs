@@ -1,7 +1,13 @@
+use strum::IntoEnumIterator;
+use strum_macros::{EnumIter, EnumString};
+use crate::data_store::textured_model_name::TexturedModelName;
+use crate::game_state::components::render::Colour;
+
 #[derive(Debug)]
 pub struct Platform {
 }
 
+#[derive(Debug)]
 pub struct Camera {
     pub r: u8,
     pub g: u8,
@@ -14,19 +20,15 @@ pub struct Camera {
     pub lookat_z: f32,
 }
 
+#[derive(Debug, Clone, PartialEq, EnumIter, EnumString)]
 pub enum OnAnimationFinish {
     Despawn,
     Repeat,
     RepeatBubble,
-    Hand3,
-    Hand2,
-    Hand1,
-    Hand0,
-    Start,
     Fryer,
-    Restart,
 }
 
+#[derive(Debug, Clone, PartialEq, EnumIter, EnumString)]
 pub enum AnimationType {
     None,
     Test,
@@ -35,13 +37,10 @@ pub enum AnimationType {
     Fryer1,
     Fryer2,
     Fryer3,
-    FryerWin,
     Bubble,
-    HandIn,
-    Hand,
-    HandOut,
 }
 
+#[derive(Debug)]
 pub struct Animation {
     pub duration: f32,
     pub past_time: f32,
@@ -57,8 +56,177 @@ pub struct ControllerAssignment {
     pub id: usize,
 }
 
+/**
+ * How well a computer-controlled player plays.
+ */
+#[derive(Debug, Clone, Copy, PartialEq, EnumIter, EnumString)]
+pub enum BotDifficulty {
+    Easy,
+    Normal,
+    Hard,
+}
+
+impl BotDifficulty {
+    /// Seconds between two moves of the bot.
+    pub fn think_time(&self) -> f32 {
+        match self {
+            BotDifficulty::Easy => 1.5,
+            BotDifficulty::Normal => 0.8,
+            BotDifficulty::Hard => 0.4,
+        }
+    }
+
+    /// The chance that a move of the bot goes in a random direction, instead of towards its fry pan.
+    pub fn mistake_chance(&self) -> f32 {
+        match self {
+            BotDifficulty::Easy => 0.4,
+            BotDifficulty::Normal => 0.2,
+            BotDifficulty::Hard => 0.05,
+        }
+    }
+}
+
+/**
+ * A player that is controlled by the computer, taking a fry pan nobody joined with.
+ * Its moves go through a controller of its own (c.f. `BOT_CONTROLLER_OFFSET`), just like the Wii Remote of a player would.
+ */
+#[derive(Debug)]
+pub struct Bot {
+    pub difficulty: BotDifficulty,
+    /// Seconds until the next move.
+    pub thinking: f32,
+}
+
+/// Bots use the controller with this index plus the id of their fry pan, which no Wii Remote has.
+pub const BOT_CONTROLLER_OFFSET: usize = 4;
+
+/**
+ * A potato in the match. Once it drops below the plate it has landed, in a fry pan or in the oil,
+ * and it stays landed until it is put back on the plate (c.f. `RespawnBehaviour`).
+ */
+#[derive(Debug)]
+pub struct Potato {
+    pub kind: PotatoKind,
+    pub landed: bool,
+}
+
+/**
+ * The kinds of potatoes there are. Each looks, rolls and scores differently.
+ */
+#[derive(Debug, Clone, Copy, PartialEq, EnumIter, EnumString)]
+pub enum PotatoKind {
+    /// Worth a single point.
+    Regular,
+    /// Big and heavy, so it is hard to push around, but it is worth two points.
+    Sweet,
+    /// Small and light, so it is hard to control, but it is worth five points.
+    Golden,
+}
+
+impl PotatoKind {
+    /// The model potatoes of this kind are drawn with.
+    pub fn model_name(&self) -> TexturedModelName {
+        match self {
+            PotatoKind::Regular => TexturedModelName::Potato,
+            PotatoKind::Sweet => TexturedModelName::SweetPotato,
+            PotatoKind::Golden => TexturedModelName::GoldenPotato,
+        }
+    }
+
+    /// The points a fry pan scores for catching a potato of this kind.
+    pub fn points(&self) -> usize {
+        match self {
+            PotatoKind::Regular => 1,
+            PotatoKind::Sweet => 2,
+            PotatoKind::Golden => 5,
+        }
+    }
+
+    /// The radius of the collider of a potato of this kind.
+    pub fn radius(&self) -> f32 {
+        match self {
+            PotatoKind::Regular => 1.0,
+            PotatoKind::Sweet => 1.3,
+            PotatoKind::Golden => 0.8,
+        }
+    }
+
+    /// The mass of the physics body of a potato of this kind.
+    pub fn mass(&self) -> f32 {
+        match self {
+            PotatoKind::Regular => 10.0,
+            PotatoKind::Sweet => 16.0,
+            PotatoKind::Golden => 6.0,
+        }
+    }
+
+    /// The joints of the physics body of a potato of this kind, as an offset from its center and a radius.
+    /// Every joint is connected to all others.
+    pub fn joints(&self) -> &'static [((f32, f32, f32), f32)] {
+        match self {
+            PotatoKind::Regular => &[((0.0, -0.3, 0.0), 0.4), ((0.0, 0.0, 0.0), 1.0), ((0.0, 0.3, 0.0), 0.5)],
+            PotatoKind::Sweet => &[((-0.6, 0.0, 0.0), 0.6), ((-0.2, 0.0, 0.0), 1.0), ((0.2, 0.0, 0.0), 1.0), ((0.6, 0.0, 0.0), 0.6)],
+            PotatoKind::Golden => &[((0.0, -0.2, 0.0), 0.6), ((0.0, 0.2, 0.0), 0.6)],
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct FryAssignment {
     pub id: usize,
     pub score: usize,
-}
\ No newline at end of file
+}
+
+/**
+ * The colour of a player, which is the colour of their fry pan.
+ * In the order the fry pans are numbered in (c.f. `FryAssignment::id`), which is also the order players get them in.
+ */
+#[derive(Debug, Clone, Copy, PartialEq, EnumIter, EnumString)]
+pub enum PanColour {
+    Black,
+    White,
+    Blue,
+    Red,
+}
+
+impl PanColour {
+    /// The model of the fry pan of this colour.
+    pub fn model_name(&self) -> TexturedModelName {
+        match self {
+            PanColour::Black => TexturedModelName::FryPanBlack,
+            PanColour::White => TexturedModelName::FryPanWhite,
+            PanColour::Blue => TexturedModelName::FryPanBlue,
+            PanColour::Red => TexturedModelName::FryPanRed,
+        }
+    }
+
+    /// The id of the fry pan of this colour, c.f. `FryAssignment::id`.
+    pub fn fry_pan_id(&self) -> usize {
+        *self as usize
+    }
+
+    /// The colour of the fry pan with the given id, c.f. `FryAssignment::id`.
+    pub fn from_fry_pan_id(id: usize) -> Option<PanColour> {
+        PanColour::iter().nth(id)
+    }
+
+    /// The colour to show this player with on screen.
+    pub fn colour(&self) -> Colour {
+        match self {
+            PanColour::Black => Colour::rgb(0x30, 0x30, 0x30),
+            PanColour::White => Colour::rgb(0xE0, 0xE0, 0xE0),
+            PanColour::Blue => Colour::rgb(0x30, 0x60, 0xD0),
+            PanColour::Red => Colour::rgb(0xD0, 0x30, 0x30),
+        }
+    }
+}
+
+/**
+ * Someone who joined the match with their Wii Remote (c.f. the `ControllerAssignment` on the same entity).
+ */
+#[derive(Debug)]
+pub struct Player {
+    pub colour: PanColour,
+    /// Whether the player wants the match to start.
+    pub ready: bool,
+}
@@ -0,0 +1,24 @@
+use crate::game_state::components::render::Colour;
+
+/**
+ * Lights up the meshes of the game state it is in.
+ * As soon as a game state has any lights, its meshes are only as bright as the lights make them.
+ * Without any lights, meshes are drawn unlit, at their full brightness.
+ */
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Light {
+    /// Shines on every mesh from the same direction, like the sun.
+    Directional { direction: (f32, f32, f32), colour: Colour },
+    /// Shines in all directions from the position of its entity, at half its brightness `radius` away.
+    Point { radius: f32, colour: Colour },
+    /// Lights every mesh equally from all sides, so the parts no other light reaches are not pitch black.
+    Ambient { colour: Colour },
+}
+
+impl Light {
+    pub fn colour(&self) -> Colour {
+        match *self {
+            Light::Directional { colour, .. } | Light::Point { colour, .. } | Light::Ambient { colour } => colour,
+        }
+    }
+}
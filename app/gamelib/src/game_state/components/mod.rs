@@ -3,3 +3,8 @@ pub mod audio;
 pub mod render;
 pub mod game;
 pub mod physics;
+pub mod timeline;
+pub mod tween;
+pub mod modulated;
+pub mod light;
+pub mod particles;
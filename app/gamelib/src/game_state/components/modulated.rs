@@ -0,0 +1,103 @@
+use alloc::boxed::Box;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::f32::consts::PI;
+use micromath::F32Ext;
+use modulator::Modulator;
+use modulator::sources::{Newtonian, ScalarGoalFollower, ScalarSpring, ShiftRegister, ShiftRegisterInterp, Wave};
+use rand::RngCore;
+use strum_macros::{EnumIter, EnumString};
+
+/**
+ * A field of a component that a modulator can drive.
+ */
+#[derive(Debug, Clone, Copy, PartialEq, EnumIter, EnumString)]
+pub enum ModulatedField {
+    PositionX,
+    PositionY,
+    PositionZ,
+    /// In degrees.
+    RotationX,
+    RotationY,
+    RotationZ,
+    /// Where the camera looks at.
+    LookAtX,
+    LookAtY,
+    LookAtZ,
+    /// The volume of the audio.
+    Volume,
+}
+
+/**
+ * A modulator from the `modulator` crate, with the settings to build it.
+ */
+#[derive(Debug, Clone, PartialEq)]
+pub enum ModulatorSource {
+    /// A sine wave (`Wave`), moving up and down by `amplitude`, `frequency` times per second.
+    /// The `phase` (from 0 to 1) is where in the wave it starts.
+    Wave { amplitude: f32, frequency: f32, phase: f32 },
+    /// Springs (`ScalarSpring`) from one random goal between `min` and `max` to the next,
+    /// taking about `smooth` seconds to get there, and pausing `pause` seconds in between.
+    Wander { min: f32, max: f32, smooth: f32, pause: f32 },
+    /// Speeds up and slows down (`Newtonian`) from one random goal between `min` and `max` to the next,
+    /// and pauses `pause` seconds in between.
+    Glide { min: f32, max: f32, speed: f32, acceleration: f32, pause: f32 },
+    /// Glides along `buckets` random values between `min` and `max` (`ShiftRegister`), going past all of them every `period` seconds.
+    /// Every time it leaves a value, there is an `odds` chance (from 0 to 1) that it is replaced by a new one.
+    Steps { min: f32, max: f32, buckets: usize, period: f32, odds: f32 },
+}
+
+impl ModulatorSource {
+    /// Builds a fresh modulator, starting at the beginning.
+    /// The random ones are seeded from the given generator, so they move the same way every time it gives the same numbers.
+    pub fn build(&self, rng: &mut impl RngCore) -> Box<dyn Modulator<f32>> {
+        match *self {
+            ModulatorSource::Wave { amplitude, frequency, phase } => {
+                Box::new(Wave::new(amplitude, frequency).wave(Box::new(move |wave: &Wave, time: f32| {
+                    wave.amplitude * ((time * wave.frequency + phase) * 2.0 * PI).sin()
+                })))
+            }
+            ModulatorSource::Wander { min, max, smooth, pause } => {
+                let spring = ScalarSpring::new(smooth, 0.0, (min + max) / 2.0);
+                Box::new(goal_follower(Box::new(spring), min, max, pause).with_seed(rng.next_u64()))
+            }
+            ModulatorSource::Glide { min, max, speed, acceleration, pause } => {
+                let newtonian = Newtonian::new([speed, speed], [acceleration, acceleration], [acceleration, acceleration], (min + max) / 2.0)
+                    .with_seed(rng.next_u64());
+                Box::new(goal_follower(Box::new(newtonian), min, max, pause).with_seed(rng.next_u64()))
+            }
+            ModulatorSource::Steps { min, max, buckets, period, odds } => {
+                Box::new(ShiftRegister::new(buckets, [min, max], odds, period, ShiftRegisterInterp::Linear).with_seed(rng.next_u64()))
+            }
+        }
+    }
+}
+
+fn goal_follower(follower: Box<dyn Modulator<f32>>, min: f32, max: f32, pause: f32) -> ScalarGoalFollower {
+    let pause = (pause * 1.0e6) as u64;
+    let mut goal_follower = ScalarGoalFollower::new(follower);
+    goal_follower.regions = vec![[min, max]];
+    goal_follower.pause_range = [pause, pause];
+    goal_follower
+}
+
+/**
+ * Drives a single field with a modulator.
+ */
+#[derive(Debug, Clone, PartialEq)]
+pub struct Modulation {
+    pub field: ModulatedField,
+    pub source: ModulatorSource,
+    /// The value the modulator moves the field around.
+    /// When it is not given, it is the value the field had when it was first modulated.
+    pub base: Option<f32>,
+}
+
+/**
+ * Drives fields of the other components of its entity with modulators, like a fry pan that wobbles or a camera that sways.
+ * The modulators themselves live in `GameState::modulators`, and are built when the entity is first modulated.
+ */
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Modulated {
+    pub modulations: Vec<Modulation>,
+}
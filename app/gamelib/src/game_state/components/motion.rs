@@ -18,3 +18,30 @@ pub struct Rotation {
     pub y: f32,
     pub z: f32,
 }
+
+/// How much bigger the mesh of the entity is drawn along each axis, 1 being its original size.
+/// Entities without one are drawn at their original size.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Scale {
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+}
+
+impl Scale {
+    pub const ONE: Scale = Scale::uniform(1.0);
+
+    /// The same scale along every axis.
+    pub const fn uniform(scale: f32) -> Scale {
+        Scale { x: scale, y: scale, z: scale }
+    }
+}
+
+/// Position of the entity before the last simulation step.
+/// Kept up to date by the game state itself, so renderers can interpolate between steps.
+#[derive(Debug)]
+pub struct PreviousPosition {
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+}
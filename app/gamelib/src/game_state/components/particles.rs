@@ -0,0 +1,127 @@
+use alloc::vec::Vec;
+use crate::game_state::components::render::Colour;
+
+/**
+ * A single particle of a `ParticleEmitter`.
+ */
+#[derive(Debug, Clone, PartialEq)]
+pub struct Particle {
+    /// In the world rather than relative to the emitter, so particles stay behind when their emitter moves.
+    pub position: (f32, f32, f32),
+    pub velocity: (f32, f32, f32),
+    /// How many seconds ago the particle was emitted.
+    pub age: f32,
+}
+
+/**
+ * Emits small, short-lived particles from the position of its entity, like a splash of oil or steam.
+ * The particles are simulated by `system_particles`, and drawn as billboards that always face the camera.
+ */
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParticleEmitter {
+    /// How many particles are emitted per second.
+    pub rate: f32,
+    /// How many particles are emitted at once, as soon as the emitter starts.
+    pub burst: usize,
+    /// How many seconds the emitter keeps emitting. Without one, it emits forever.
+    /// Once it is done and all of its particles are gone, its entity is despawned.
+    pub duration: Option<f32>,
+    /// How many seconds every particle lives.
+    pub lifetime: f32,
+    /// The direction the particles are emitted in.
+    pub direction: (f32, f32, f32),
+    /// The particles go off at most this many degrees away from the `direction`.
+    pub spread: f32,
+    /// Every particle is emitted at a random speed in between these two.
+    pub min_speed: f32,
+    pub max_speed: f32,
+    /// How fast the particles speed up downwards.
+    pub gravity: f32,
+    /// The colour of a particle when it is emitted, fading into `end_colour` over its life.
+    pub start_colour: Colour,
+    pub end_colour: Colour,
+    /// The width of a particle when it is emitted, growing or shrinking into `end_size` over its life.
+    pub start_size: f32,
+    pub end_size: f32,
+    /// No more particles are emitted while this many are alive.
+    pub max_particles: usize,
+    /// How many seconds the emitter has been emitting.
+    pub elapsed: f32,
+    /// The part of a particle that is due, but can only be emitted once it adds up to a whole one.
+    pub pending: f32,
+    pub particles: Vec<Particle>,
+}
+
+impl ParticleEmitter {
+    /// An emitter that keeps emitting `rate` white particles per second straight up, that live for `lifetime` seconds.
+    pub fn new(rate: f32, lifetime: f32) -> ParticleEmitter {
+        ParticleEmitter {
+            rate,
+            burst: 0,
+            duration: None,
+            lifetime,
+            direction: (0.0, 1.0, 0.0),
+            spread: 0.0,
+            min_speed: 1.0,
+            max_speed: 1.0,
+            gravity: 0.0,
+            start_colour: Colour::WHITE,
+            end_colour: Colour::WHITE,
+            start_size: 0.5,
+            end_size: 0.5,
+            max_particles: 64,
+            elapsed: 0.0,
+            pending: 0.0,
+            particles: Vec::new(),
+        }
+    }
+
+    /// A single burst of drops of the given colour, flying up and falling back down.
+    pub fn splash(colour: Colour) -> ParticleEmitter {
+        ParticleEmitter {
+            burst: 24,
+            duration: Some(0.0),
+            spread: 35.0,
+            min_speed: 6.0,
+            max_speed: 11.0,
+            gravity: 30.0,
+            start_colour: colour,
+            end_colour: colour.with_alpha(0x00),
+            start_size: 0.6,
+            end_size: 0.2,
+            max_particles: 24,
+            ..ParticleEmitter::new(0.0, 0.8)
+        }
+    }
+
+    /// Whether the emitter stopped emitting for good.
+    /// An emitter with a duration of zero emits its burst on its first step, and is done after it.
+    pub fn is_done(&self) -> bool {
+        self.duration.map_or(false, |duration| self.elapsed > duration)
+    }
+
+    /// The colour of a particle of the given age.
+    pub fn colour_at(&self, age: f32) -> Colour {
+        let t = self.life_fraction(age);
+        let channel = |start: u8, end: u8| (start as f32 + (end as f32 - start as f32) * t) as u8;
+        Colour {
+            r: channel(self.start_colour.r, self.end_colour.r),
+            g: channel(self.start_colour.g, self.end_colour.g),
+            b: channel(self.start_colour.b, self.end_colour.b),
+            a: channel(self.start_colour.a, self.end_colour.a),
+        }
+    }
+
+    /// The size of a particle of the given age.
+    pub fn size_at(&self, age: f32) -> f32 {
+        self.start_size + (self.end_size - self.start_size) * self.life_fraction(age)
+    }
+
+    /// How far along its life a particle of the given age is, from 0 to 1.
+    fn life_fraction(&self, age: f32) -> f32 {
+        if self.lifetime <= 0.0 {
+            return 1.0;
+        }
+        (age / self.lifetime).clamp(0.0, 1.0)
+    }
+}
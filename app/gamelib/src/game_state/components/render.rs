@@ -1,5 +1,50 @@
 use crate::data_store::textured_model_name::TexturedModelName;
 
 pub struct MeshInstance {
-    pub model_name: TexturedModelName
-}
\ No newline at end of file
+    pub model_name: TexturedModelName,
+    /// Multiplied with the texture of the model, so `Colour::WHITE` draws the model as it is.
+    /// The alpha makes the model see-through.
+    pub tint: Colour,
+}
+
+impl MeshInstance {
+    /// An instance of the model, drawn as it is.
+    pub fn new(model_name: TexturedModelName) -> MeshInstance {
+        MeshInstance { model_name, tint: Colour::WHITE }
+    }
+
+    /// An instance of the model, drawn in the given colour.
+    pub fn tinted(model_name: TexturedModelName, tint: Colour) -> MeshInstance {
+        MeshInstance { model_name, tint }
+    }
+}
+
+/**
+ * A colour with an alpha channel, each channel from 0 to 255.
+ */
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Colour {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+    pub a: u8,
+}
+
+impl Colour {
+    pub const WHITE: Colour = Colour::rgb(0xFF, 0xFF, 0xFF);
+    pub const BLACK: Colour = Colour::rgb(0x00, 0x00, 0x00);
+
+    pub const fn rgb(r: u8, g: u8, b: u8) -> Colour {
+        Colour { r, g, b, a: 0xFF }
+    }
+
+    /// The same colour, with the given alpha.
+    pub const fn with_alpha(&self, a: u8) -> Colour {
+        Colour { r: self.r, g: self.g, b: self.b, a }
+    }
+
+    /// Packs the colour as 0xRRGGBBAA, the way GRRLIB and GX expect it.
+    pub const fn to_rgba(&self) -> u32 {
+        (self.r as u32) << 24 | (self.g as u32) << 16 | (self.b as u32) << 8 | self.a as u32
+    }
+}
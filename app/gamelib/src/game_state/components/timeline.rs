@@ -0,0 +1,144 @@
+use strum_macros::{EnumIter, EnumString};
+use crate::data_store::textured_model_name::TexturedModelName;
+
+/**
+ * Something a timeline tells the game when it reaches a keyframe (c.f. `GameEvent::TimelineSignal`).
+ */
+#[derive(Debug, Clone, Copy, PartialEq, EnumIter, EnumString)]
+pub enum TimelineSignal {
+    /// The countdown is over, the match starts.
+    StartMatch,
+    /// The match is over and celebrated, a new one can start.
+    RestartMatch,
+}
+
+/**
+ * A change a timeline makes to its entity at a point in time.
+ */
+#[derive(Debug, Clone, PartialEq)]
+pub enum KeyframeChange {
+    /// Moves the entity, relative to the origin of the timeline. In between, the position moves along linearly.
+    Position(f32, f32, f32),
+    /// Rotates the entity, in degrees. In between, the rotation turns along linearly.
+    Rotation(f32, f32, f32),
+    /// Swaps the mesh of the entity.
+    Mesh(TexturedModelName),
+    /// Emits the signal once, when the timeline passes this keyframe.
+    Signal(TimelineSignal),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Keyframe {
+    /// Seconds since the start of the timeline.
+    pub time: f32,
+    pub change: KeyframeChange,
+}
+
+const fn keyframe(time: f32, change: KeyframeChange) -> Keyframe {
+    Keyframe { time, change }
+}
+
+/**
+ * What a timeline does once it has played all its keyframes.
+ */
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TimelineEnd {
+    /// Keeps the entity as the last keyframes left it.
+    Hold,
+    /// Starts over from the beginning.
+    Loop,
+    /// Despawns the entity.
+    Despawn,
+    /// Continues with another timeline.
+    Chain(TimelineName),
+}
+
+/**
+ * Enumerates all timelines that exist in the project.
+ * Each of them can be turned into its keyframes by calling `keyframes()` on it.
+ */
+#[derive(Debug, Clone, Copy, PartialEq, EnumIter, EnumString)]
+pub enum TimelineName {
+    /// A hand counting down from three before the match, after which it starts.
+    Countdown,
+    /// The fry pan of the winner rises up...
+    WinRise,
+    /// ...and spins around, after which a new match starts.
+    WinSpin,
+    /// Keeps turning around, to show something off.
+    Spin,
+}
+
+const COUNTDOWN: &[Keyframe] = &[
+    keyframe(0.0, KeyframeChange::Position(0.0, 0.0, 0.0)),
+    keyframe(0.0, KeyframeChange::Rotation(0.0, 90.0, 10.0)),
+    keyframe(0.0, KeyframeChange::Mesh(TexturedModelName::HandThree)),
+    keyframe(1.5, KeyframeChange::Position(1.0, 15.0, 15.0)),
+    keyframe(1.5, KeyframeChange::Rotation(0.0, 90.0, 0.0)),
+    keyframe(1.5, KeyframeChange::Mesh(TexturedModelName::HandTwo)),
+    keyframe(2.5, KeyframeChange::Mesh(TexturedModelName::HandOne)),
+    keyframe(3.5, KeyframeChange::Position(1.0, 15.0, 15.0)),
+    keyframe(3.5, KeyframeChange::Mesh(TexturedModelName::HandFist)),
+    keyframe(4.0, KeyframeChange::Position(0.0, 5.0, 40.0)),
+    keyframe(4.0, KeyframeChange::Signal(TimelineSignal::StartMatch)),
+];
+
+const WIN_RISE: &[Keyframe] = &[
+    keyframe(0.0, KeyframeChange::Position(0.0, 0.0, 0.0)),
+    keyframe(2.0, KeyframeChange::Position(0.0, 5.0, 0.0)),
+];
+
+const WIN_SPIN: &[Keyframe] = &[
+    keyframe(0.0, KeyframeChange::Rotation(0.0, 0.0, 0.0)),
+    keyframe(3.0, KeyframeChange::Rotation(0.0, 720.0, 0.0)),
+    keyframe(3.0, KeyframeChange::Signal(TimelineSignal::RestartMatch)),
+];
+
+const SPIN: &[Keyframe] = &[
+    keyframe(0.0, KeyframeChange::Rotation(0.0, 0.0, 0.0)),
+    keyframe(4.0, KeyframeChange::Rotation(0.0, 360.0, 0.0)),
+];
+
+impl TimelineName {
+    /// The keyframes of the timeline, ordered by time.
+    pub const fn keyframes(&self) -> &'static [Keyframe] {
+        match self {
+            TimelineName::Countdown => COUNTDOWN,
+            TimelineName::WinRise => WIN_RISE,
+            TimelineName::WinSpin => WIN_SPIN,
+            TimelineName::Spin => SPIN,
+        }
+    }
+
+    pub const fn end(&self) -> TimelineEnd {
+        match self {
+            TimelineName::Countdown => TimelineEnd::Despawn,
+            TimelineName::WinRise => TimelineEnd::Chain(TimelineName::WinSpin),
+            TimelineName::WinSpin => TimelineEnd::Hold,
+            TimelineName::Spin => TimelineEnd::Loop,
+        }
+    }
+
+    /// Seconds until the last keyframe.
+    pub fn duration(&self) -> f32 {
+        self.keyframes().iter().fold(0.0, |duration, keyframe| if keyframe.time > duration { keyframe.time } else { duration })
+    }
+}
+
+/**
+ * Plays a timeline on its entity: the position, rotation and mesh follow its keyframes.
+ */
+#[derive(Debug)]
+pub struct Timeline {
+    pub name: TimelineName,
+    /// Seconds since the start of the timeline.
+    pub time: f32,
+    /// Keyframe positions are relative to this position.
+    pub origin: (f32, f32, f32),
+}
+
+impl Timeline {
+    pub fn new(name: TimelineName, origin: (f32, f32, f32)) -> Timeline {
+        Timeline { name, time: 0.0, origin }
+    }
+}
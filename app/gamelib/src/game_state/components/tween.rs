@@ -0,0 +1,164 @@
+use core::f32::consts::PI;
+use micromath::F32Ext;
+use modulator::Modulator;
+use modulator::sources::{Newtonian, ScalarSpring};
+use strum_macros::{EnumIter, EnumString};
+
+/// How close a modulator has to be to the end of its tween, in progress, before the tween is done.
+const SETTLED: f32 = 0.001;
+
+/**
+ * The curves a tween can follow from its start to its end.
+ * Each of them turns the part of the duration that has passed into the part of the way that has been travelled.
+ */
+#[derive(Debug, Clone, Copy, PartialEq, EnumIter, EnumString)]
+pub enum Easing {
+    Linear,
+    QuadIn,
+    QuadOut,
+    QuadInOut,
+    CubicIn,
+    CubicOut,
+    CubicInOut,
+    /// Shoots past the end and wobbles around it before settling.
+    ElasticOut,
+    /// Pulls back a little before it starts moving.
+    BackIn,
+    /// Shoots a little past the end before coming back to it.
+    BackOut,
+}
+
+impl Easing {
+    /// The progress at the given part of the duration, both going from 0 to 1.
+    pub fn apply(&self, t: f32) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+        // The overshoot of the back curves.
+        let back = 1.70158;
+        match self {
+            Easing::Linear => t,
+            Easing::QuadIn => t * t,
+            Easing::QuadOut => 1.0 - (1.0 - t) * (1.0 - t),
+            Easing::QuadInOut if t < 0.5 => 2.0 * t * t,
+            Easing::QuadInOut => 1.0 - (2.0 - 2.0 * t) * (2.0 - 2.0 * t) / 2.0,
+            Easing::CubicIn => t * t * t,
+            Easing::CubicOut => 1.0 - (1.0 - t) * (1.0 - t) * (1.0 - t),
+            Easing::CubicInOut if t < 0.5 => 4.0 * t * t * t,
+            Easing::CubicInOut => 1.0 - (2.0 - 2.0 * t) * (2.0 - 2.0 * t) * (2.0 - 2.0 * t) / 2.0,
+            Easing::ElasticOut if t <= 0.0 || t >= 1.0 => t,
+            Easing::ElasticOut => 2.0f32.powf(-10.0 * t) * ((t * 10.0 - 0.75) * (2.0 * PI / 3.0)).sin() + 1.0,
+            Easing::BackIn => (back + 1.0) * t * t * t - back * t * t,
+            Easing::BackOut => 1.0 + (back + 1.0) * (t - 1.0) * (t - 1.0) * (t - 1.0) + back * (t - 1.0) * (t - 1.0),
+        }
+    }
+}
+
+/**
+ * The component a tween moves.
+ */
+#[derive(Debug, Clone, Copy, PartialEq, EnumIter, EnumString)]
+pub enum TweenProperty {
+    Position,
+    /// In degrees.
+    Rotation,
+    Scale,
+}
+
+/**
+ * What moves a tween along from its start to its end.
+ */
+pub enum TweenDriver {
+    /// Takes exactly the duration of the tween, following the curve.
+    Easing(Easing),
+    /// Springs towards the end, taking as long as the spring needs to settle there.
+    Spring(ScalarSpring),
+    /// Speeds up and slows down towards the end, taking as long as its speed allows.
+    Newtonian(Newtonian),
+}
+
+/**
+ * Moves a component of its entity from one value to another over time.
+ *
+ * The tween keeps track of its own progress, so it ends up in the same place at any frame rate.
+ * Once it is done, the entity is left at the end and the tween is removed.
+ * Tweens are not part of snapshots: a restored entity stays where its tween had brought it.
+ */
+pub struct Tween {
+    pub property: TweenProperty,
+    pub from: (f32, f32, f32),
+    pub to: (f32, f32, f32),
+    /// Seconds the tween takes, when driven by an easing curve.
+    pub duration: f32,
+    /// Seconds since the start of the tween.
+    pub elapsed: f32,
+    pub driver: TweenDriver,
+}
+
+impl Tween {
+    /// A tween following the easing curve, taking `duration` seconds.
+    pub fn eased(property: TweenProperty, from: (f32, f32, f32), to: (f32, f32, f32), duration: f32, easing: Easing) -> Tween {
+        Tween { property, from, to, duration, elapsed: 0.0, driver: TweenDriver::Easing(easing) }
+    }
+
+    /**
+     * A tween driven by a critically damped spring, which takes about `smooth` seconds to get there.
+     * A positive `undamp` makes it overshoot and wobble around the end.
+     */
+    pub fn spring(property: TweenProperty, from: (f32, f32, f32), to: (f32, f32, f32), smooth: f32, undamp: f32) -> Tween {
+        let mut spring = ScalarSpring::new(smooth, undamp, 0.0);
+        spring.spring_to(1.0);
+        Tween { property, from, to, duration: 0.0, elapsed: 0.0, driver: TweenDriver::Spring(spring) }
+    }
+
+    /**
+     * A tween that accelerates, keeps to its speed limit, and decelerates to stop right at the end.
+     * The speeds are in parts of the way per second: a speed limit of 0.5 takes at least two seconds.
+     */
+    pub fn newtonian(property: TweenProperty, from: (f32, f32, f32), to: (f32, f32, f32), speed_limit: f32, acceleration: f32, deceleration: f32) -> Tween {
+        let mut newtonian = Newtonian::new([speed_limit, speed_limit], [acceleration, acceleration], [deceleration, deceleration], 0.0);
+        newtonian.move_to(1.0);
+        Tween { property, from, to, duration: 0.0, elapsed: 0.0, driver: TweenDriver::Newtonian(newtonian) }
+    }
+
+    /// Moves the tween ahead by the given number of seconds.
+    pub fn advance(&mut self, seconds: f32) {
+        self.elapsed += seconds;
+        let micros = (seconds * 1.0e6) as u64;
+        match &mut self.driver {
+            TweenDriver::Easing(_) => {}
+            TweenDriver::Spring(spring) => spring.advance(micros),
+            TweenDriver::Newtonian(newtonian) => newtonian.advance(micros),
+        }
+    }
+
+    /// How far along the way the tween is: 0 at the start and 1 at the end, but it may go past those in between.
+    pub fn progress(&self) -> f32 {
+        match &self.driver {
+            TweenDriver::Easing(_) if self.duration <= 0.0 => 1.0,
+            TweenDriver::Easing(easing) => easing.apply(self.elapsed / self.duration),
+            TweenDriver::Spring(spring) => spring.value(),
+            TweenDriver::Newtonian(newtonian) => newtonian.value(),
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        match &self.driver {
+            TweenDriver::Easing(_) => self.elapsed >= self.duration,
+            TweenDriver::Spring(spring) => settled(spring.value) && spring.vel * spring.vel < SETTLED * SETTLED,
+            TweenDriver::Newtonian(newtonian) => settled(newtonian.value),
+        }
+    }
+
+    /// The value of the property at the current progress, or the end once the tween is done.
+    pub fn value(&self) -> (f32, f32, f32) {
+        let progress = if self.is_finished() { 1.0 } else { self.progress() };
+        (
+            self.from.0 + (self.to.0 - self.from.0) * progress,
+            self.from.1 + (self.to.1 - self.from.1) * progress,
+            self.from.2 + (self.to.2 - self.from.2) * progress,
+        )
+    }
+}
+
+fn settled(progress: f32) -> bool {
+    (1.0 - progress) * (1.0 - progress) < SETTLED * SETTLED
+}
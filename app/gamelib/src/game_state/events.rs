@@ -0,0 +1,99 @@
+#[cfg(feature = "wii")]
+use ogc_rs::prelude::Vec;
+#[cfg(not(feature = "wii"))]
+use std::vec::Vec;
+
+use hecs::Entity;
+use crate::game_state::changes::controls::Direction;
+use crate::game_state::components::game::PanColour;
+use crate::game_state::components::timeline::TimelineSignal;
+
+/**
+ * Something that happened in a game state, for other systems to react upon.
+ */
+#[derive(Debug, Clone, PartialEq)]
+pub enum GameEvent {
+    /// A player joined the match with the controller of the given index.
+    PlayerJoined { controller: usize, colour: PanColour },
+    /// A motion was started with the controller of the given index.
+    MotionStarted { controller: usize, direction: Direction },
+    /// A potato landed in a fry pan, which scored the given number of points.
+    PotatoScored { fry_pan: Entity, potato: Entity, points: usize },
+    /// A potato fell off the plate into the oil, missing all fry pans.
+    PotatoFellInOil { potato: Entity },
+    /// A fry pan won the match.
+    MatchWon { fry_pan: Entity },
+    /// An entity bounced against the bounds.
+    Bounced { entity: Entity },
+    /// The timeline of an entity passed a keyframe with a signal.
+    TimelineSignal { entity: Entity, signal: TimelineSignal },
+}
+
+/**
+ * Queue of the events emitted during a rendered frame.
+ *
+ * Systems in the simulation steps read the events of their own step (`this_step`),
+ * so nothing is reacted upon twice when several steps run in a frame.
+ * Presentation systems read everything that happened since the last rendered frame (`this_frame`).
+ * The queue is cleared by the `Game` after every rendered frame.
+ */
+#[derive(Debug, Default)]
+pub struct Events {
+    events: Vec<GameEvent>,
+    step_start: usize,
+}
+
+impl Events {
+    pub fn new() -> Events {
+        Events::default()
+    }
+
+    pub fn emit(&mut self, event: GameEvent) {
+        self.events.push(event);
+    }
+
+    /// The events emitted during the current simulation step, so far.
+    pub fn this_step(&self) -> &[GameEvent] {
+        &self.events[self.step_start..]
+    }
+
+    /// The events emitted during all simulation steps since the last rendered frame.
+    pub fn this_frame(&self) -> &[GameEvent] {
+        &self.events
+    }
+
+    /// Starts a new simulation step, the events of the earlier steps are only kept for presentation.
+    pub fn start_step(&mut self) {
+        self.step_start = self.events.len();
+    }
+
+    pub fn clear(&mut self) {
+        self.events.clear();
+        self.step_start = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::game_state::changes::controls::Direction;
+    use super::{Events, GameEvent};
+
+    #[test]
+    fn test_step_and_frame_events() {
+        let mut events = Events::new();
+        events.start_step();
+        events.emit(GameEvent::MotionStarted { controller: 0, direction: Direction::Xp });
+        assert_eq!(events.this_step().len(), 1);
+
+        // A second step in the same frame starts empty, but presentation still sees both.
+        events.start_step();
+        assert!(events.this_step().is_empty());
+        events.emit(GameEvent::MotionStarted { controller: 1, direction: Direction::Yn });
+        assert_eq!(events.this_step(), &[GameEvent::MotionStarted { controller: 1, direction: Direction::Yn }]);
+        assert_eq!(events.this_frame().len(), 2);
+
+        events.clear();
+        assert!(events.this_frame().is_empty());
+        assert!(events.this_step().is_empty());
+    }
+}
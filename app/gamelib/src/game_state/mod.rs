@@ -1,6 +1,12 @@
 pub mod systems;
 pub mod changes;
 pub mod components;
+pub mod events;
+pub mod rules;
+pub mod results;
+pub mod schedule;
+pub mod scene;
+pub mod snapshot;
 
 #[cfg(feature = "wii")]
 use ogc_rs::prelude::Vec;
@@ -11,12 +17,24 @@ use alloc::rc::Rc;
 #[cfg(not(feature = "wii"))]
 use std::rc::Rc;
 
-use hecs::World;
+use core::time::Duration;
+use hecs::{Entity, World};
+use modulator::ModulatorEnv;
+use rand::rngs::SmallRng;
+use rand::SeedableRng;
+use strum_macros::EnumIter;
 use core::cell::RefCell;
+use components::motion::{Position, PreviousPosition};
 use changes::Changes;
+use events::{Events, GameEvent};
+use rules::MatchRules;
+use results::MatchResults;
 use systems::system_name::SystemName;
-use crate::{game_states::GameStateName, servers::ServerProvider};
+use schedule::{Schedule, ScheduledSystem, SystemPhase};
+use components::physics::SphereCollider;
+use crate::{game_states::{GameStateName, Transition}, servers::ServerProvider, servers::renderer::RenderStats};
 
+#[derive(Debug, Clone, Copy, PartialEq, EnumIter)]
 pub enum PlayMode {
     Selection,
     Hands,
@@ -30,11 +48,36 @@ pub enum PlayMode {
 pub struct GameState {
     pub world: World,
     pub playmode: PlayMode,
+    /// The rules of the match played in this state.
+    pub rules: MatchRules,
+    /// How long the current match has been played.
+    pub match_time: Duration,
+    /// How long the lobby has been waiting for someone to join, c.f. `ATTRACT_TIMEOUT`.
+    pub lobby_time: Duration,
+    /// The results of the last match, once it is over.
+    /// They are handed on to the next state, so the results screen can show them and a rematch can be set up.
+    pub results: Option<MatchResults>,
     pub changes: Changes,
-    pub next_state: Option<GameStateName>,
+    /// Source of all randomness in this state, seeded when the state is built.
+    /// Draw from this instead of seeding a generator of your own, so replays stay deterministic.
+    pub rng: SmallRng,
+    /// What happened this frame, c.f. `Events`.
+    pub events: Events,
+    /// The modulators that drive the `Modulated` components, c.f. `system_modulate`.
+    pub modulators: ModulatorEnv<f32>,
+    /// The state to go to after this frame, if any.
+    pub transition: Option<Transition>,
+    /// Whether the states below this one (on the state stack) are still drawn.
+    pub is_overlay: bool,
+    /// Whether the states below this one (on the state stack) keep running their simulation.
+    pub updates_below: bool,
     pub is_running: bool,
     pub server_provider: Option<Rc<RefCell<ServerProvider>>>,
-    systems: Vec<SystemName>,
+    /// How far the rendered frame is between the last simulation step and the next one, from 0 to 1.
+    pub interpolation: f32,
+    /// How many meshes of this state were drawn and culled in the last rendered frame.
+    pub render_stats: RenderStats,
+    schedule: Schedule,
 }
 
 /**
@@ -42,35 +85,159 @@ pub struct GameState {
  */
 impl GameState {
     pub fn new() -> GameState {
+        GameState::with_seed(0)
+    }
+
+    /**
+     * A new, empty game state, whose random numbers are drawn from the given seed.
+     */
+    pub fn with_seed(seed: u64) -> GameState {
         GameState {
             world: World::new(),
             playmode: PlayMode::Selection,
+            rules: MatchRules::standard(),
+            match_time: Duration::ZERO,
+            lobby_time: Duration::ZERO,
+            results: None,
             changes: Changes::nothing(),
-            systems: Vec::new(),
+            rng: SmallRng::seed_from_u64(seed),
+            events: Events::new(),
+            modulators: ModulatorEnv::new(),
+            schedule: Schedule::new(),
             is_running: true,
             server_provider: None::<Rc<RefCell<ServerProvider>>>,
-            next_state: None,
+            transition: None,
+            is_overlay: false,
+            updates_below: false,
+            interpolation: 1.0,
+            render_stats: RenderStats::default(),
         }
     }
 
     /**
-     * Add a new system to the game state.
+     * Replace this state by a fresh instance of the given state after this frame.
+     */
+    pub fn replace_state(&mut self, name: GameStateName) {
+        self.transition = Some(Transition::Replace(name));
+    }
+
+    /**
+     * Replace this state by the given state after this frame, continuing where that state was left the last time.
+     * When the state was not played before, it starts fresh.
+     */
+    pub fn resume_state(&mut self, name: GameStateName) {
+        self.transition = Some(Transition::Resume(name));
+    }
+
+    /**
+     * Put the given state on top of this one after this frame. This state is frozen until the other one is popped.
+     */
+    pub fn push_state(&mut self, name: GameStateName) {
+        self.transition = Some(Transition::Push(name));
+    }
+
+    /**
+     * Remove this state after this frame, continuing the state below it.
+     */
+    pub fn pop_state(&mut self) {
+        self.transition = Some(Transition::Pop);
+    }
+
+    /**
+     * Add a new system to the game state, in the phase given by `SystemName::default_phase`.
+     * The returned entry can be used to change its phase, ordering and run condition.
+     */
+    pub fn add_system(&mut self, system: SystemName) -> &mut ScheduledSystem {
+        self.schedule.add(system)
+    }
+
+    pub fn is_system_enabled(&self, system: &SystemName) -> bool {
+        self.schedule.is_enabled(system)
+    }
+
+    /**
+     * Enable or disable a system that was added earlier, from then on.
      */
-    pub fn add_system(&mut self, system: SystemName) {
-        self.systems.push(system);
+    pub fn set_system_enabled(&mut self, system: &SystemName, enabled: bool) {
+        self.schedule.set_enabled(system, enabled);
     }
 
-    /// Performs game logic of one iteration of the game loop.
+    /// Performs game logic of one simulation step of the game loop.
     /// Before calling this, the caller should have filled `Changes` with the required values.
     ///
     /// If the returned bool is `true`, the game loop should continue.
     /// When `false` is returned, the game should quit.
     pub fn update(&mut self, changes: Changes) -> bool {
+        self.simulate(changes);
+        if self.uses_physics() {
+            if let Some(server_provider) = &self.server_provider {
+                server_provider.borrow_mut().physics_server.world_step();
+            }
+        }
+        self.sync_physics()
+    }
+
+    /// The first half of `update`: the input and simulation phases, up to stepping the physics world.
+    pub fn simulate(&mut self, changes: Changes) {
         self.changes = changes;
-        let cloned_systems = self.systems.to_vec();
-        for system in cloned_systems {
+        self.events.start_step();
+        self.emit_input_events();
+        self.store_previous_positions();
+        self.run_phase(SystemPhase::Input);
+        self.run_phase(SystemPhase::Simulation);
+    }
+
+    /// The second half of `update`: the phase after the physics world has been stepped.
+    pub fn sync_physics(&mut self) -> bool {
+        self.run_phase(SystemPhase::PhysicsSync);
+        return self.is_running
+    }
+
+    /// Whether this state has anything in the physics world, so the world should be stepped for it.
+    pub fn uses_physics(&self) -> bool {
+        self.world.query::<&SphereCollider>().iter().next().is_some()
+    }
+
+    /// Performs the presentation of one rendered frame, like rendering and playing audio.
+    pub fn render(&mut self) {
+        self.run_phase(SystemPhase::Presentation);
+    }
+
+    /// Forgets the events of this frame, after it has been rendered.
+    pub fn end_frame(&mut self) {
+        self.events.clear();
+    }
+
+    fn emit_input_events(&mut self) {
+        for (controller, control) in self.changes.controls.wii_mote_controls.iter().enumerate() {
+            if let Some(motion) = &control.motion {
+                if motion.started {
+                    self.events.emit(GameEvent::MotionStarted { controller, direction: motion.direction });
+                }
+            }
+        }
+    }
+
+    fn run_phase(&mut self, phase: SystemPhase) {
+        // The systems are looked up every phase, so a system can switch others on or off.
+        let systems = self.schedule.systems_to_run(phase, self.playmode);
+        for system in systems {
             system.to_function()(self);
         }
-        return self.is_running
+    }
+
+    /// Remembers where every entity was before this step, to interpolate between steps when rendering.
+    fn store_previous_positions(&mut self) {
+        let mut missing: Vec<(Entity, PreviousPosition)> = Vec::new();
+        for (id, (position, previous)) in self.world.query_mut::<(&Position, Option<&mut PreviousPosition>)>() {
+            let current = PreviousPosition { x: position.x, y: position.y, z: position.z };
+            match previous {
+                Some(previous) => *previous = current,
+                None => missing.push((id, current)),
+            }
+        }
+        for (id, previous) in missing {
+            let _ = self.world.insert_one(id, previous);
+        }
     }
 }
\ No newline at end of file
@@ -0,0 +1,72 @@
+use alloc::vec::Vec;
+use crate::game_state::components::game::{BotDifficulty, PanColour};
+use crate::game_state::rules::MatchRules;
+
+/**
+ * How a single fry pan ended the match.
+ */
+#[derive(Debug, Clone, PartialEq)]
+pub struct Standing {
+    pub colour: PanColour,
+    pub score: usize,
+    /// The controller of whoever played the fry pan.
+    pub controller: usize,
+    /// Set when a bot played the fry pan, with the difficulty it played at.
+    pub bot: Option<BotDifficulty>,
+}
+
+/**
+ * The final scores of a match, handed from the match to the results screen (c.f. `GameState::results`),
+ * and back again for a rematch.
+ */
+#[derive(Debug, Clone, PartialEq)]
+pub struct MatchResults {
+    /// Best score first.
+    pub standings: Vec<Standing>,
+    /// The rules the match was played with, so a rematch is played with the same ones.
+    pub rules: MatchRules,
+}
+
+impl MatchResults {
+    /// Ranks the standings from the best to the worst score. Equal scores keep the order of their fry pans.
+    pub fn new(mut standings: Vec<Standing>, rules: MatchRules) -> MatchResults {
+        standings.sort_by(|a, b| b.score.cmp(&a.score).then(a.colour.fry_pan_id().cmp(&b.colour.fry_pan_id())));
+        MatchResults { standings, rules }
+    }
+
+    /// The place (1 for the winner) of every standing. Equal scores share their place, and the next place is skipped.
+    pub fn places(&self) -> Vec<usize> {
+        let mut places: Vec<usize> = Vec::new();
+        for (index, standing) in self.standings.iter().enumerate() {
+            let place = match index {
+                0 => 1,
+                _ if self.standings[index - 1].score == standing.score => places[index - 1],
+                _ => index + 1,
+            };
+            places.push(place);
+        }
+        places
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+    use crate::game_state::components::game::{BotDifficulty, PanColour};
+    use crate::game_state::rules::MatchRules;
+    use super::{MatchResults, Standing};
+
+    #[test]
+    fn test_ranking() {
+        let standing = |colour: PanColour, score: usize| Standing { colour, score, controller: colour.fry_pan_id(), bot: None };
+        let results = MatchResults::new(vec![
+            standing(PanColour::Black, 8),
+            Standing { bot: Some(BotDifficulty::Hard), ..standing(PanColour::White, 21) },
+            standing(PanColour::Blue, 8),
+            standing(PanColour::Red, 3),
+        ], MatchRules::standard());
+        let colours: Vec<PanColour> = results.standings.iter().map(|standing| standing.colour).collect();
+        assert_eq!(colours, vec![PanColour::White, PanColour::Black, PanColour::Blue, PanColour::Red]);
+        assert_eq!(results.places(), vec![1, 2, 2, 4]);
+    }
+}
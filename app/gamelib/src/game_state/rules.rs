@@ -0,0 +1,170 @@
+use core::time::Duration;
+use rand::RngCore;
+use strum::IntoEnumIterator;
+use strum_macros::EnumIter;
+use crate::game_state::components::game::{BotDifficulty, PotatoKind};
+use crate::game_state::components::motion::Position;
+
+/**
+ * What happens to a potato that falls off the plate into the oil.
+ */
+#[derive(Debug, Clone, Copy, PartialEq, EnumIter)]
+pub enum RespawnBehaviour {
+    /// It is put back above the plate.
+    AbovePlate,
+    /// It is gone for the rest of the match. Once all potatoes are gone, the match is over.
+    Never,
+}
+
+impl RespawnBehaviour {
+    /// Where a potato that landed is put back, or `None` when it is gone for good.
+    /// Potatoes are dropped from a random spot above the middle of the plate, so they do not all stack up.
+    pub fn spawn_position(&self, rng: &mut impl RngCore) -> Option<Position> {
+        match self {
+            RespawnBehaviour::AbovePlate => {
+                let mut random = |min: f32, max: f32| min + (max - min) * (rng.next_u32() as f32 / u32::MAX as f32);
+                Some(Position { x: random(-2.0, 2.0), y: 10.0, z: random(-2.0, 2.0) })
+            }
+            RespawnBehaviour::Never => None,
+        }
+    }
+}
+
+/**
+ * How often each kind of potato is spawned, relative to each other.
+ */
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PotatoMix {
+    pub regular: u32,
+    pub sweet: u32,
+    pub golden: u32,
+}
+
+impl PotatoMix {
+    /// Only regular potatoes, the way the game started out.
+    pub fn only_regular() -> PotatoMix {
+        PotatoMix { regular: 1, sweet: 0, golden: 0 }
+    }
+
+    pub fn weight(&self, kind: PotatoKind) -> u32 {
+        match kind {
+            PotatoKind::Regular => self.regular,
+            PotatoKind::Sweet => self.sweet,
+            PotatoKind::Golden => self.golden,
+        }
+    }
+
+    /// Picks a kind at random, each as likely as its weight. Without any weights, the potato is regular.
+    pub fn pick(&self, rng: &mut impl RngCore) -> PotatoKind {
+        let total = self.regular + self.sweet + self.golden;
+        if total == 0 {
+            return PotatoKind::Regular;
+        }
+        let mut roll = rng.next_u32() % total;
+        for kind in PotatoKind::iter() {
+            let weight = self.weight(kind);
+            if roll < weight {
+                return kind;
+            }
+            roll -= weight;
+        }
+        PotatoKind::Regular
+    }
+}
+
+/**
+ * The rules of a match, chosen before it starts and read by the gamemaster systems.
+ */
+#[derive(Debug, Clone, PartialEq)]
+pub struct MatchRules {
+    /// A fry pan with at least this score wins the match.
+    pub score_target: usize,
+    /// When set, the match ends after this long, and the highest score wins.
+    pub time_limit: Option<Duration>,
+    /// Number of potatoes spawned when the match starts.
+    pub potato_count: usize,
+    /// Maximum number of players that can join, each with their own fry pan. At most 4.
+    pub player_count: usize,
+    pub respawn: RespawnBehaviour,
+    /// Which kinds of potatoes are spawned when the match starts.
+    pub potato_mix: PotatoMix,
+    /// When set, the fry pans nobody joined with are played by bots of this difficulty, up to `player_count`.
+    pub bots: Option<BotDifficulty>,
+}
+
+impl MatchRules {
+    /// First to 21, as long as it takes, with only regular potatoes.
+    pub fn standard() -> MatchRules {
+        MatchRules {
+            score_target: 21,
+            time_limit: None,
+            potato_count: 20,
+            player_count: 4,
+            respawn: RespawnBehaviour::AbovePlate,
+            potato_mix: PotatoMix::only_regular(),
+            bots: Some(BotDifficulty::Normal),
+        }
+    }
+
+    /// The standard match, with the odd sweet or golden potato.
+    pub fn mixed() -> MatchRules {
+        MatchRules {
+            potato_mix: PotatoMix { regular: 14, sweet: 5, golden: 1 },
+            ..MatchRules::standard()
+        }
+    }
+
+    /// A short match of at most a minute and a half.
+    pub fn quick() -> MatchRules {
+        MatchRules {
+            score_target: 10,
+            time_limit: Some(Duration::from_secs(90)),
+            potato_count: 12,
+            ..MatchRules::standard()
+        }
+    }
+
+    /// Every potato only counts once: whoever catches most of them wins.
+    pub fn sudden_death() -> MatchRules {
+        MatchRules {
+            score_target: 11,
+            respawn: RespawnBehaviour::Never,
+            ..MatchRules::standard()
+        }
+    }
+}
+
+impl Default for MatchRules {
+    fn default() -> Self {
+        MatchRules::standard()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::rngs::SmallRng;
+    use rand::SeedableRng;
+    use crate::game_state::components::game::PotatoKind;
+    use super::{MatchRules, PotatoMix};
+
+    #[test]
+    fn test_potato_mix() {
+        let mut rng = SmallRng::seed_from_u64(3);
+        let only_regular = PotatoMix::only_regular();
+        assert!((0..50).all(|_| only_regular.pick(&mut rng) == PotatoKind::Regular));
+
+        let only_golden = PotatoMix { regular: 0, sweet: 0, golden: 2 };
+        assert!((0..50).all(|_| only_golden.pick(&mut rng) == PotatoKind::Golden));
+
+        let mixed = PotatoMix { regular: 1, sweet: 1, golden: 0 };
+        let sweet = (0..1000).filter(|_| mixed.pick(&mut rng) == PotatoKind::Sweet).count();
+        assert!(sweet > 400 && sweet < 600);
+    }
+
+    #[test]
+    fn test_only_mixed_rules_spawn_other_kinds() {
+        assert_eq!(MatchRules::standard().potato_mix, PotatoMix::only_regular());
+        assert_eq!(MatchRules::quick().potato_mix, PotatoMix::only_regular());
+        assert!(MatchRules::mixed().potato_mix.golden > 0);
+    }
+}
@@ -0,0 +1,630 @@
+use core::str::{FromStr, SplitWhitespace};
+use hecs::{EntityBuilder, World};
+use rand::RngCore;
+#[cfg(feature = "wii")]
+use ogc_rs::prelude::Vec;
+#[cfg(not(feature = "wii"))]
+use std::vec::Vec;
+
+use crate::data_store::asset_name::AssetName;
+use crate::data_store::textured_model_name::TexturedModelName;
+use crate::game_state::changes::controls::WII_MOTE_COUNT;
+use crate::game_state::components::audio::Audio;
+use crate::game_state::components::game::*;
+use crate::game_state::components::light::Light;
+use crate::game_state::components::modulated::{Modulated, ModulatedField, Modulation, ModulatorSource};
+use crate::game_state::components::motion::{Position, Rotation, Scale, Velocity};
+use crate::game_state::components::particles::ParticleEmitter;
+use crate::game_state::components::physics::SphereCollider;
+use crate::game_state::components::render::{Colour, MeshInstance};
+use crate::servers::audio::PlayMode;
+
+/**
+ * Why a scene could not be loaded, together with the (1-based) line it went wrong on.
+ */
+#[derive(Debug, PartialEq)]
+pub struct SceneError {
+    pub line: usize,
+    pub kind: SceneErrorKind,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum SceneErrorKind {
+    /// The scene file is not valid UTF-8.
+    NotText,
+    /// A component was given before the first `entity` line.
+    ComponentOutsideEntity,
+    UnknownComponent,
+    /// A component is missing one of its values.
+    MissingValue,
+    /// A value could not be read, like a number that is not a number or an unknown mesh name.
+    InvalidValue,
+}
+
+/**
+ * Spawns all entities described by a scene file from the data store into the world.
+ * Random ranges in the scene are drawn from the given random number generator.
+ *
+ * The format is line based, and described at the top of `data/scenes/bouncing_cubes.scene`.
+ */
+pub fn load_scene(world: &mut World, scene: AssetName, rng: &mut impl RngCore) -> Result<(), SceneError> {
+    let source = core::str::from_utf8(scene.to_data()).map_err(|_| SceneError { line: 0, kind: SceneErrorKind::NotText })?;
+    load_scene_source(world, source, rng)
+}
+
+/**
+ * Spawns all entities described by the given scene source into the world.
+ * Nothing is spawned unless the whole scene could be read.
+ */
+pub fn load_scene_source(world: &mut World, source: &str, rng: &mut impl RngCore) -> Result<(), SceneError> {
+    // Collect the lines of every entity first, as the entity is spawned once its last line has been read.
+    let mut entities: Vec<(usize, Vec<(usize, &str)>)> = Vec::new();
+    for (index, raw_line) in source.lines().enumerate() {
+        let line_number = index + 1;
+        let line = raw_line.split('#').next().unwrap_or("").trim();
+        if line.is_empty() {
+            continue;
+        }
+        let mut words = line.split_whitespace();
+        if words.next() == Some("entity") {
+            let _name = words.next().ok_or(SceneError { line: line_number, kind: SceneErrorKind::MissingValue })?;
+            let count = match words.next() {
+                None => 1,
+                Some(count) => usize::from_str(count).map_err(|_| SceneError { line: line_number, kind: SceneErrorKind::InvalidValue })?,
+            };
+            if words.next().is_some() {
+                return Err(SceneError { line: line_number, kind: SceneErrorKind::InvalidValue });
+            }
+            entities.push((count, Vec::new()));
+        } else {
+            match entities.last_mut() {
+                Some((_count, lines)) => lines.push((line_number, line)),
+                None => return Err(SceneError { line: line_number, kind: SceneErrorKind::ComponentOutsideEntity }),
+            }
+        }
+    }
+
+    let mut builders = Vec::new();
+    for (count, lines) in entities.iter() {
+        for _ in 0..*count {
+            let mut builder = EntityBuilder::new();
+            let mut position = (0.0, 0.0, 0.0);
+            let mut modulations = Vec::new();
+            for (line_number, line) in lines.iter() {
+                add_component(&mut builder, line, &mut position, &mut modulations, rng)
+                    .map_err(|kind| SceneError { line: *line_number, kind })?;
+            }
+            if !modulations.is_empty() {
+                builder.add(Modulated { modulations });
+            }
+            builders.push(builder);
+        }
+    }
+    for mut builder in builders {
+        world.spawn(builder.build());
+    }
+    Ok(())
+}
+
+/**
+ * Parses a single component line, and adds it to the entity being built.
+ * `position` keeps track of the position of the entity, so later lines can refer to it.
+ * Every `modulate` line adds to `modulations`, which end up in a single `Modulated` component.
+ */
+fn add_component(
+    builder: &mut EntityBuilder,
+    line: &str,
+    position: &mut (f32, f32, f32),
+    modulations: &mut Vec<Modulation>,
+    rng: &mut impl RngCore,
+) -> Result<(), SceneErrorKind> {
+    let mut words = line.split_whitespace();
+    let component = words.next().ok_or(SceneErrorKind::MissingValue)?;
+    match component {
+        "mesh" => {
+            let model_name = parse_name::<TexturedModelName>(&mut words)?;
+            let mut mesh = MeshInstance::new(model_name);
+            for (key, value) in properties(words)? {
+                match key {
+                    "tint" => mesh.tint = parse_colour(value, position, rng)?.with_alpha(mesh.tint.a),
+                    "alpha" => mesh.tint.a = parse_channel(value, rng)?,
+                    _ => return Err(SceneErrorKind::InvalidValue),
+                }
+            }
+            builder.add(mesh);
+        }
+        "position" => {
+            let (x, y, z) = parse_vector(&mut words, rng)?;
+            *position = (x, y, z);
+            builder.add(Position { x, y, z });
+        }
+        "rotation" => {
+            let (x, y, z) = parse_vector(&mut words, rng)?;
+            builder.add(Rotation { x, y, z });
+        }
+        "scale" => {
+            let (x, y, z) = parse_vector(&mut words, rng)?;
+            builder.add(Scale { x, y, z });
+        }
+        "velocity" => {
+            let (x, y, z) = parse_vector(&mut words, rng)?;
+            builder.add(Velocity { x, y, z });
+        }
+        "camera" => {
+            let mut camera = Camera {
+                r: 0, g: 0, b: 0,
+                up_x: 0.0, up_y: 1.0, up_z: 0.0,
+                lookat_x: 0.0, lookat_y: 0.0, lookat_z: 0.0,
+            };
+            for (key, value) in properties(words)? {
+                match key {
+                    "background" => {
+                        let background = parse_colour(value, position, rng)?;
+                        camera.r = background.r;
+                        camera.g = background.g;
+                        camera.b = background.b;
+                    }
+                    "up" => (camera.up_x, camera.up_y, camera.up_z) = parse_triple(value, position, rng)?,
+                    "look_at" => (camera.lookat_x, camera.lookat_y, camera.lookat_z) = parse_triple(value, position, rng)?,
+                    _ => return Err(SceneErrorKind::InvalidValue),
+                }
+            }
+            builder.add(camera);
+        }
+        "animation" => {
+            let mut animation = Animation {
+                duration: 0.0,
+                past_time: 0.0,
+                animation_type: AnimationType::None,
+                on_animation_finish: OnAnimationFinish::Despawn,
+                target_x: position.0,
+                target_y: position.1,
+                target_z: position.2,
+            };
+            for (key, value) in properties(words)? {
+                match key {
+                    "type" => animation.animation_type = AnimationType::from_str(value).map_err(|_| SceneErrorKind::InvalidValue)?,
+                    "on_finish" => animation.on_animation_finish = OnAnimationFinish::from_str(value).map_err(|_| SceneErrorKind::InvalidValue)?,
+                    "duration" => animation.duration = parse_number(value, rng)?,
+                    "past_time" => animation.past_time = parse_number(value, rng)?,
+                    "target" => (animation.target_x, animation.target_y, animation.target_z) = parse_triple(value, position, rng)?,
+                    _ => return Err(SceneErrorKind::InvalidValue),
+                }
+            }
+            builder.add(animation);
+        }
+        "audio" => {
+            let asset_name = parse_name::<AssetName>(&mut words)?;
+            let play_mode = parse_name::<PlayMode>(&mut words)?;
+            builder.add(Audio::new(asset_name, play_mode));
+        }
+        "collider" => {
+            let mut collider = SphereCollider { radius: 1.0, gravity: true, body_index: 0, has_been_registered: false };
+            for (key, value) in properties(words)? {
+                match key {
+                    "radius" => collider.radius = parse_number(value, rng)?,
+                    "gravity" => collider.gravity = bool::from_str(value).map_err(|_| SceneErrorKind::InvalidValue)?,
+                    _ => return Err(SceneErrorKind::InvalidValue),
+                }
+            }
+            builder.add(collider);
+        }
+        "controller" => {
+            let id = parse_name::<usize>(&mut words)?;
+            if id >= WII_MOTE_COUNT {
+                return Err(SceneErrorKind::InvalidValue);
+            }
+            builder.add(ControllerAssignment { id });
+        }
+        "fry_pan" => {
+            let id = parse_name::<usize>(&mut words)?;
+            builder.add(FryAssignment { id, score: 0 });
+        }
+        "platform" => {
+            builder.add(Platform {});
+        }
+        "potato" => {
+            let kind = parse_name::<PotatoKind>(&mut words)?;
+            builder.add(Potato { kind, landed: false });
+        }
+        "light" => {
+            let mut light = match words.next().ok_or(SceneErrorKind::MissingValue)? {
+                "directional" => Light::Directional { direction: (0.0, -1.0, 0.0), colour: Colour::WHITE },
+                "point" => Light::Point { radius: 10.0, colour: Colour::WHITE },
+                "ambient" => Light::Ambient { colour: Colour::WHITE },
+                _ => return Err(SceneErrorKind::InvalidValue),
+            };
+            for (key, value) in properties(words)? {
+                match (&mut light, key) {
+                    (Light::Directional { colour, .. } | Light::Point { colour, .. } | Light::Ambient { colour }, "colour") => {
+                        *colour = parse_colour(value, position, rng)?;
+                    }
+                    (Light::Directional { direction, .. }, "direction") => *direction = parse_triple(value, position, rng)?,
+                    (Light::Point { radius, .. }, "radius") => *radius = parse_number(value, rng)?,
+                    _ => return Err(SceneErrorKind::InvalidValue),
+                }
+            }
+            builder.add(light);
+        }
+        "emitter" => {
+            let mut emitter = ParticleEmitter::new(10.0, 1.0);
+            // Unless given, particles keep the colour and alpha they were emitted with.
+            let mut end_colour = None;
+            let mut end_alpha = None;
+            for (key, value) in properties(words)? {
+                match key {
+                    "rate" => emitter.rate = parse_number(value, rng)?,
+                    "burst" => emitter.burst = parse_number(value, rng)? as usize,
+                    "duration" => emitter.duration = Some(parse_number(value, rng)?),
+                    "lifetime" => emitter.lifetime = parse_number(value, rng)?,
+                    "direction" => emitter.direction = parse_triple(value, position, rng)?,
+                    "spread" => emitter.spread = parse_number(value, rng)?,
+                    "min_speed" => emitter.min_speed = parse_number(value, rng)?,
+                    "max_speed" => emitter.max_speed = parse_number(value, rng)?,
+                    "gravity" => emitter.gravity = parse_number(value, rng)?,
+                    "colour" => emitter.start_colour = parse_colour(value, position, rng)?.with_alpha(emitter.start_colour.a),
+                    "alpha" => emitter.start_colour.a = parse_channel(value, rng)?,
+                    "end_colour" => end_colour = Some(parse_colour(value, position, rng)?),
+                    "end_alpha" => end_alpha = Some(parse_channel(value, rng)?),
+                    "size" => {
+                        emitter.start_size = parse_number(value, rng)?;
+                        emitter.end_size = emitter.start_size;
+                    }
+                    "end_size" => emitter.end_size = parse_number(value, rng)?,
+                    "max" => emitter.max_particles = parse_number(value, rng)? as usize,
+                    _ => return Err(SceneErrorKind::InvalidValue),
+                }
+            }
+            emitter.end_colour = end_colour.unwrap_or(emitter.start_colour)
+                .with_alpha(end_alpha.unwrap_or(emitter.start_colour.a));
+            builder.add(emitter);
+        }
+        "modulate" => {
+            let field = parse_name::<ModulatedField>(&mut words)?;
+            let mut source = match words.next().ok_or(SceneErrorKind::MissingValue)? {
+                "wave" => ModulatorSource::Wave { amplitude: 1.0, frequency: 1.0, phase: 0.0 },
+                "wander" => ModulatorSource::Wander { min: -1.0, max: 1.0, smooth: 1.0, pause: 0.0 },
+                "glide" => ModulatorSource::Glide { min: -1.0, max: 1.0, speed: 1.0, acceleration: 1.0, pause: 0.0 },
+                "steps" => ModulatorSource::Steps { min: -1.0, max: 1.0, buckets: 8, period: 4.0, odds: 0.25 },
+                _ => return Err(SceneErrorKind::InvalidValue),
+            };
+            for (key, value) in properties(words)? {
+                let number = parse_number(value, rng)?;
+                match (&mut source, key) {
+                    (ModulatorSource::Wave { amplitude, .. }, "amplitude") => *amplitude = number,
+                    (ModulatorSource::Wave { frequency, .. }, "frequency") => *frequency = number,
+                    (ModulatorSource::Wave { phase, .. }, "phase") => *phase = number,
+                    (ModulatorSource::Wander { min, .. } | ModulatorSource::Glide { min, .. } | ModulatorSource::Steps { min, .. }, "min") => *min = number,
+                    (ModulatorSource::Wander { max, .. } | ModulatorSource::Glide { max, .. } | ModulatorSource::Steps { max, .. }, "max") => *max = number,
+                    (ModulatorSource::Wander { pause, .. } | ModulatorSource::Glide { pause, .. }, "pause") => *pause = number,
+                    (ModulatorSource::Wander { smooth, .. }, "smooth") => *smooth = number,
+                    (ModulatorSource::Glide { speed, .. }, "speed") => *speed = number,
+                    (ModulatorSource::Glide { acceleration, .. }, "acceleration") => *acceleration = number,
+                    (ModulatorSource::Steps { buckets, .. }, "buckets") => *buckets = number as usize,
+                    (ModulatorSource::Steps { period, .. }, "period") => *period = number,
+                    (ModulatorSource::Steps { odds, .. }, "odds") => *odds = number,
+                    _ => return Err(SceneErrorKind::InvalidValue),
+                }
+            }
+            // The random modulators pick their values between min and max, so there has to be something in between.
+            match source {
+                ModulatorSource::Wander { min, max, .. } | ModulatorSource::Glide { min, max, .. } | ModulatorSource::Steps { min, max, .. } if min >= max => {
+                    return Err(SceneErrorKind::InvalidValue)
+                }
+                ModulatorSource::Steps { buckets: 0, .. } => return Err(SceneErrorKind::InvalidValue),
+                _ => {}
+            }
+            modulations.push(Modulation { field, source, base: None });
+        }
+        _ => return Err(SceneErrorKind::UnknownComponent),
+    }
+    Ok(())
+}
+
+fn parse_name<T: FromStr>(words: &mut SplitWhitespace) -> Result<T, SceneErrorKind> {
+    let word = words.next().ok_or(SceneErrorKind::MissingValue)?;
+    T::from_str(word).map_err(|_| SceneErrorKind::InvalidValue)
+}
+
+/// Splits `key=value` words.
+fn properties(words: SplitWhitespace) -> Result<Vec<(&str, &str)>, SceneErrorKind> {
+    words.map(|word| word.split_once('=').ok_or(SceneErrorKind::InvalidValue)).collect()
+}
+
+/// Reads a number, or a random number when given as `min..max`.
+fn parse_number(value: &str, rng: &mut impl RngCore) -> Result<f32, SceneErrorKind> {
+    match value.split_once("..") {
+        None => f32::from_str(value).map_err(|_| SceneErrorKind::InvalidValue),
+        Some((min, max)) => {
+            let min = f32::from_str(min).map_err(|_| SceneErrorKind::InvalidValue)?;
+            let max = f32::from_str(max).map_err(|_| SceneErrorKind::InvalidValue)?;
+            let t = rng.next_u32() as f32 / u32::MAX as f32;
+            Ok(min + (max - min) * t)
+        }
+    }
+}
+
+/// Reads three numbers given as separate words.
+fn parse_vector(words: &mut SplitWhitespace, rng: &mut impl RngCore) -> Result<(f32, f32, f32), SceneErrorKind> {
+    let x = parse_number(words.next().ok_or(SceneErrorKind::MissingValue)?, rng)?;
+    let y = parse_number(words.next().ok_or(SceneErrorKind::MissingValue)?, rng)?;
+    let z = parse_number(words.next().ok_or(SceneErrorKind::MissingValue)?, rng)?;
+    Ok((x, y, z))
+}
+
+/// Reads three comma separated numbers, where `@` stands for the same axis of the position.
+fn parse_triple(value: &str, position: &(f32, f32, f32), rng: &mut impl RngCore) -> Result<(f32, f32, f32), SceneErrorKind> {
+    let mut parts = value.split(',');
+    let mut axis = |same_as_position: f32| -> Result<f32, SceneErrorKind> {
+        match parts.next() {
+            None => Err(SceneErrorKind::MissingValue),
+            Some("@") => Ok(same_as_position),
+            Some(part) => parse_number(part, rng),
+        }
+    };
+    let x = axis(position.0)?;
+    let y = axis(position.1)?;
+    let z = axis(position.2)?;
+    Ok((x, y, z))
+}
+
+/// Reads a colour as three comma separated channels, c.f. `parse_channel`.
+fn parse_colour(value: &str, position: &(f32, f32, f32), rng: &mut impl RngCore) -> Result<Colour, SceneErrorKind> {
+    let (r, g, b) = parse_triple(value, position, rng)?;
+    Ok(Colour::rgb(to_channel(r)?, to_channel(g)?, to_channel(b)?))
+}
+
+/// Reads a single colour channel or alpha, from 0 up to 255.
+fn parse_channel(value: &str, rng: &mut impl RngCore) -> Result<u8, SceneErrorKind> {
+    to_channel(parse_number(value, rng)?)
+}
+
+fn to_channel(number: f32) -> Result<u8, SceneErrorKind> {
+    if (0.0..=255.0).contains(&number) {
+        Ok(number as u8)
+    } else {
+        Err(SceneErrorKind::InvalidValue)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use hecs::World;
+    use rand::rngs::SmallRng;
+    use rand::SeedableRng;
+    use crate::data_store::asset_name::AssetName;
+    use crate::data_store::textured_model_name::TexturedModelName;
+    use crate::game_state::components::game::{Animation, Camera, FryAssignment};
+    use crate::game_state::components::light::Light;
+    use crate::game_state::components::modulated::{Modulated, ModulatedField, ModulatorSource};
+    use crate::game_state::components::motion::{Position, Scale};
+    use crate::game_state::components::particles::ParticleEmitter;
+    use crate::game_state::components::render::{Colour, MeshInstance};
+    use super::{load_scene, load_scene_source, SceneError, SceneErrorKind};
+
+    fn count_meshes(world: &World, model_name: TexturedModelName) -> usize {
+        world.query::<&MeshInstance>().iter().filter(|(_e, mesh)| mesh.model_name == model_name).count()
+    }
+
+    #[test]
+    fn test_all_scenes_load() {
+        let mut rng = SmallRng::seed_from_u64(10u64);
+        for scene in [
+            AssetName::BouncingCubesScene,
+            AssetName::MatchScene,
+            AssetName::MovingPlatformScene,
+            AssetName::PauseScene,
+            AssetName::PotatoScene,
+            AssetName::ResultsScene,
+        ] {
+            let mut world = World::new();
+            assert_eq!(load_scene(&mut world, scene, &mut rng), Ok(()));
+        }
+
+        let mut world = World::new();
+        load_scene(&mut world, AssetName::BouncingCubesScene, &mut rng).unwrap();
+        assert_eq!(world.query::<&Camera>().iter().count(), 1);
+        assert_eq!(count_meshes(&world, TexturedModelName::Plate), 1);
+        assert_eq!(count_meshes(&world, TexturedModelName::OilBubble), 20);
+
+        let mut world = World::new();
+        load_scene(&mut world, AssetName::MatchScene, &mut rng).unwrap();
+        assert_eq!(world.query::<&FryAssignment>().iter().count(), 4);
+        assert_eq!(count_meshes(&world, TexturedModelName::Potato), 0);
+        load_scene(&mut world, AssetName::PotatoScene, &mut rng).unwrap();
+        assert_eq!(count_meshes(&world, TexturedModelName::Potato), 1);
+    }
+
+    #[test]
+    fn test_ranges_and_position_references() {
+        let source = "
+            # Some bubbles
+            entity bubble 50
+                position -20..20 -6 5
+                animation type=Bubble on_finish=RepeatBubble duration=1 target=@,0,@
+        ";
+        let mut world = World::new();
+        load_scene_source(&mut world, source, &mut SmallRng::seed_from_u64(1u64)).unwrap();
+        let mut query = world.query::<(&Position, &Animation)>();
+        assert_eq!(query.iter().count(), 50);
+        for (_e, (position, animation)) in query.iter() {
+            assert!(position.x >= -20.0 && position.x <= 20.0);
+            assert_eq!(position.y, -6.0);
+            assert_eq!(animation.target_x, position.x);
+            assert_eq!(animation.target_y, 0.0);
+            assert_eq!(animation.target_z, 5.0);
+        }
+    }
+
+    #[test]
+    fn test_modulations() {
+        let source = "
+            entity fry_pan
+                rotation 0 0 0
+                modulate RotationZ wave amplitude=3 frequency=0.5..1
+                modulate PositionY steps min=-1 max=1 buckets=4
+        ";
+        let mut world = World::new();
+        load_scene_source(&mut world, source, &mut SmallRng::seed_from_u64(1u64)).unwrap();
+        let mut query = world.query::<&Modulated>();
+        let (_e, modulated) = query.iter().next().unwrap();
+        assert_eq!(modulated.modulations.len(), 2);
+        assert_eq!(modulated.modulations[0].field, ModulatedField::RotationZ);
+        match modulated.modulations[0].source {
+            ModulatorSource::Wave { amplitude, frequency, phase } => {
+                assert_eq!((amplitude, phase), (3.0, 0.0));
+                assert!(frequency >= 0.5 && frequency <= 1.0);
+            }
+            _ => panic!("Expected a wave"),
+        }
+        assert_eq!(modulated.modulations[1].source, ModulatorSource::Steps { min: -1.0, max: 1.0, buckets: 4, period: 4.0, odds: 0.25 });
+
+        let mut world = World::new();
+        assert_eq!(
+            load_scene_source(&mut world, "entity a\n  modulate PositionY wave smooth=1", &mut SmallRng::seed_from_u64(1u64)),
+            Err(SceneError { line: 2, kind: SceneErrorKind::InvalidValue })
+        );
+    }
+
+    #[test]
+    fn test_scale_and_tint() {
+        let source = "
+            entity ghost
+                mesh FryPanWhite tint=255,64,0 alpha=128
+                scale 2 1 2
+        ";
+        let mut world = World::new();
+        load_scene_source(&mut world, source, &mut SmallRng::seed_from_u64(1u64)).unwrap();
+        let mut query = world.query::<(&MeshInstance, &Scale)>();
+        let (_e, (mesh, scale)) = query.iter().next().unwrap();
+        assert_eq!(mesh.tint, Colour { r: 255, g: 64, b: 0, a: 128 });
+        assert_eq!(*scale, Scale { x: 2.0, y: 1.0, z: 2.0 });
+
+        let mut world = World::new();
+        load_scene_source(&mut world, "entity plate\n  mesh Plate", &mut SmallRng::seed_from_u64(1u64)).unwrap();
+        assert_eq!(world.query::<&MeshInstance>().iter().next().unwrap().1.tint, Colour::WHITE);
+    }
+
+    #[test]
+    fn test_lights() {
+        let source = "
+            entity sun
+                light directional direction=1,-1,0 colour=255,240,200
+            entity lamp
+                position 0 5 0
+                light point radius=6
+            entity sky
+                light ambient colour=40,40,60
+        ";
+        let mut world = World::new();
+        load_scene_source(&mut world, source, &mut SmallRng::seed_from_u64(1u64)).unwrap();
+        let lights: Vec<Light> = world.query::<&Light>().iter().map(|(_e, light)| *light).collect();
+        assert!(lights.contains(&Light::Directional { direction: (1.0, -1.0, 0.0), colour: Colour::rgb(255, 240, 200) }));
+        assert!(lights.contains(&Light::Point { radius: 6.0, colour: Colour::WHITE }));
+        assert!(lights.contains(&Light::Ambient { colour: Colour::rgb(40, 40, 60) }));
+
+        let mut world = World::new();
+        assert_eq!(
+            load_scene_source(&mut world, "entity a\n  light ambient radius=2", &mut SmallRng::seed_from_u64(1u64)),
+            Err(SceneError { line: 2, kind: SceneErrorKind::InvalidValue })
+        );
+    }
+
+    #[test]
+    fn test_emitters() {
+        let source = "
+            entity steam
+                position 0 -6 0
+                emitter rate=4 lifetime=2 spread=15 colour=255,230,200 alpha=90 end_alpha=0 size=0.5 end_size=2
+            entity sparks
+                emitter burst=10 duration=0 direction=1,0,0 min_speed=3 max_speed=5 gravity=9 end_colour=255,0,0 max=10
+        ";
+        let mut world = World::new();
+        load_scene_source(&mut world, source, &mut SmallRng::seed_from_u64(1u64)).unwrap();
+        let emitters: Vec<ParticleEmitter> = world.query::<&ParticleEmitter>().iter().map(|(_e, emitter)| emitter.clone()).collect();
+        let steam = emitters.iter().find(|emitter| emitter.rate == 4.0).unwrap();
+        assert_eq!((steam.lifetime, steam.spread, steam.duration), (2.0, 15.0, None));
+        assert_eq!(steam.start_colour, Colour { r: 255, g: 230, b: 200, a: 90 });
+        assert_eq!(steam.end_colour, Colour { r: 255, g: 230, b: 200, a: 0 });
+        assert_eq!((steam.start_size, steam.end_size), (0.5, 2.0));
+
+        let sparks = emitters.iter().find(|emitter| emitter.burst == 10).unwrap();
+        assert_eq!((sparks.duration, sparks.direction, sparks.gravity), (Some(0.0), (1.0, 0.0, 0.0), 9.0));
+        assert_eq!((sparks.min_speed, sparks.max_speed, sparks.max_particles), (3.0, 5.0, 10));
+        assert_eq!(sparks.end_colour, Colour::rgb(255, 0, 0));
+
+        let mut world = World::new();
+        assert_eq!(
+            load_scene_source(&mut world, "entity a\n  emitter speed=2", &mut SmallRng::seed_from_u64(1u64)),
+            Err(SceneError { line: 2, kind: SceneErrorKind::InvalidValue })
+        );
+    }
+
+    #[test]
+    fn test_errors() {
+        let mut rng = SmallRng::seed_from_u64(1u64);
+        let mut world = World::new();
+        assert_eq!(
+            load_scene_source(&mut world, "mesh Plate", &mut rng),
+            Err(SceneError { line: 1, kind: SceneErrorKind::ComponentOutsideEntity })
+        );
+        assert_eq!(
+            load_scene_source(&mut world, "entity a\n  mesh Plate\n  wobble 1", &mut rng),
+            Err(SceneError { line: 3, kind: SceneErrorKind::UnknownComponent })
+        );
+        assert_eq!(
+            load_scene_source(&mut world, "entity a\n  mesh NotAMesh", &mut rng),
+            Err(SceneError { line: 2, kind: SceneErrorKind::InvalidValue })
+        );
+        assert_eq!(
+            load_scene_source(&mut world, "entity a\n  position 1 2", &mut rng),
+            Err(SceneError { line: 2, kind: SceneErrorKind::MissingValue })
+        );
+        assert_eq!(
+            load_scene_source(&mut world, "entity a 2 extra\n  mesh Plate", &mut rng),
+            Err(SceneError { line: 1, kind: SceneErrorKind::InvalidValue })
+        );
+        assert_eq!(
+            load_scene_source(&mut world, "entity a\n  modulate PositionX wander min=2 max=1", &mut rng),
+            Err(SceneError { line: 2, kind: SceneErrorKind::InvalidValue })
+        );
+        assert_eq!(
+            load_scene_source(&mut world, "entity a\n  modulate PositionY glide min=1 max=1", &mut rng),
+            Err(SceneError { line: 2, kind: SceneErrorKind::InvalidValue })
+        );
+        assert_eq!(
+            load_scene_source(&mut world, "entity a\n  modulate PositionZ steps min=0 max=-1", &mut rng),
+            Err(SceneError { line: 2, kind: SceneErrorKind::InvalidValue })
+        );
+        assert_eq!(
+            load_scene_source(&mut world, "entity a\n  modulate PositionZ steps buckets=0", &mut rng),
+            Err(SceneError { line: 2, kind: SceneErrorKind::InvalidValue })
+        );
+        assert_eq!(
+            load_scene_source(&mut world, "entity a\n  mesh Plate tint=255,256,0", &mut rng),
+            Err(SceneError { line: 2, kind: SceneErrorKind::InvalidValue })
+        );
+        assert_eq!(
+            load_scene_source(&mut world, "entity a\n  camera background=-1,0,0", &mut rng),
+            Err(SceneError { line: 2, kind: SceneErrorKind::InvalidValue })
+        );
+        assert_eq!(
+            load_scene_source(&mut world, "entity a\n  light point colour=0,0,300", &mut rng),
+            Err(SceneError { line: 2, kind: SceneErrorKind::InvalidValue })
+        );
+        assert_eq!(
+            load_scene_source(&mut world, "entity a\n  emitter end_alpha=1000", &mut rng),
+            Err(SceneError { line: 2, kind: SceneErrorKind::InvalidValue })
+        );
+        assert_eq!(
+            load_scene_source(&mut world, "entity a\n  controller 4", &mut rng),
+            Err(SceneError { line: 2, kind: SceneErrorKind::InvalidValue })
+        );
+        // A broken entity further down leaves the world as it was.
+        assert_eq!(
+            load_scene_source(&mut world, "entity a\n  mesh Plate\nentity b\n  mesh NotAMesh", &mut rng),
+            Err(SceneError { line: 4, kind: SceneErrorKind::InvalidValue })
+        );
+        assert_eq!(world.len(), 0);
+    }
+}
@@ -0,0 +1,262 @@
+#[cfg(feature = "wii")]
+use ogc_rs::prelude::Vec;
+#[cfg(not(feature = "wii"))]
+use std::vec::Vec;
+
+use super::PlayMode;
+use super::systems::system_name::SystemName;
+
+/**
+ * The phases a game state runs its systems in, in this order.
+ * All but `Presentation` run every simulation step, `Presentation` runs once every rendered frame.
+ */
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+pub enum SystemPhase {
+    /// Reacting to the controls.
+    Input,
+    /// Game logic, before the physics world is stepped.
+    Simulation,
+    /// Reading the results of the physics step back into the components.
+    PhysicsSync,
+    /// Rendering, camera and audio.
+    Presentation,
+}
+
+/**
+ * Decides whether a system runs, based on the current play mode.
+ */
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum RunCondition {
+    Always,
+    InPlayMode(PlayMode),
+    NotInPlayMode(PlayMode),
+}
+
+impl RunCondition {
+    pub fn allows(&self, playmode: PlayMode) -> bool {
+        match self {
+            RunCondition::Always => true,
+            RunCondition::InPlayMode(mode) => *mode == playmode,
+            RunCondition::NotInPlayMode(mode) => *mode != playmode,
+        }
+    }
+}
+
+/**
+ * A system that was added to a game state, together with when it should run.
+ * Returned by `GameState::add_system`, so the defaults can be changed right away:
+ *
+ * `state.add_system(SystemName::PatatoControl).run_if(RunCondition::InPlayMode(PlayMode::Playing));`
+ */
+pub struct ScheduledSystem {
+    pub name: SystemName,
+    pub phase: SystemPhase,
+    pub enabled: bool,
+    pub run_condition: RunCondition,
+    after: Vec<SystemName>,
+    before: Vec<SystemName>,
+}
+
+impl ScheduledSystem {
+    /// Runs the system in another phase than its default one.
+    pub fn in_phase(&mut self, phase: SystemPhase) -> &mut Self {
+        self.phase = phase;
+        self
+    }
+
+    /// Makes sure the system runs after the other one, when both are in the same phase.
+    pub fn after(&mut self, other: SystemName) -> &mut Self {
+        self.after.push(other);
+        self
+    }
+
+    /// Makes sure the system runs before the other one, when both are in the same phase.
+    pub fn before(&mut self, other: SystemName) -> &mut Self {
+        self.before.push(other);
+        self
+    }
+
+    pub fn run_if(&mut self, run_condition: RunCondition) -> &mut Self {
+        self.run_condition = run_condition;
+        self
+    }
+
+    /// Adds the system without running it, until it is enabled.
+    pub fn disabled(&mut self) -> &mut Self {
+        self.enabled = false;
+        self
+    }
+}
+
+/**
+ * All systems of a game state, and the order to run them in.
+ *
+ * Systems run phase by phase. Within a phase they run in the order they were added,
+ * unless a before/after constraint says otherwise: a system that has to run before another one
+ * takes the place of that other one, instead of its own.
+ */
+pub struct Schedule {
+    systems: Vec<ScheduledSystem>,
+    /// Indices into `systems` in the order they run; only valid when not dirty.
+    order: Vec<usize>,
+    dirty: bool,
+}
+
+impl Schedule {
+    pub fn new() -> Schedule {
+        Schedule {
+            systems: Vec::new(),
+            order: Vec::new(),
+            dirty: false,
+        }
+    }
+
+    pub fn add(&mut self, name: SystemName) -> &mut ScheduledSystem {
+        self.dirty = true;
+        self.systems.push(ScheduledSystem {
+            phase: name.default_phase(),
+            name,
+            enabled: true,
+            run_condition: RunCondition::Always,
+            after: Vec::new(),
+            before: Vec::new(),
+        });
+        self.systems.last_mut().unwrap()
+    }
+
+    pub fn contains(&self, name: &SystemName) -> bool {
+        self.systems.iter().any(|system| &system.name == name)
+    }
+
+    pub fn is_enabled(&self, name: &SystemName) -> bool {
+        self.systems.iter().any(|system| &system.name == name && system.enabled)
+    }
+
+    /// Enables or disables every occurrence of the system.
+    pub fn set_enabled(&mut self, name: &SystemName, enabled: bool) {
+        for system in self.systems.iter_mut().filter(|system| &system.name == name) {
+            system.enabled = enabled;
+        }
+    }
+
+    /**
+     * The systems of the given phase that should run in the given play mode, in order.
+     */
+    pub fn systems_to_run(&mut self, phase: SystemPhase, playmode: PlayMode) -> Vec<SystemName> {
+        if self.dirty {
+            self.order = self.sort();
+            self.dirty = false;
+        }
+        self.order.iter()
+            .map(|index| &self.systems[*index])
+            .filter(|system| system.phase == phase && system.enabled && system.run_condition.allows(playmode))
+            .map(|system| system.name.clone())
+            .collect()
+    }
+
+    /// Orders the systems by phase, and within a phase topologically on the before/after constraints.
+    /// Ties are broken by the order the systems were added in, where a system that has to run before others
+    /// counts as added at the earliest of their places.
+    fn sort(&self) -> Vec<usize> {
+        let count = self.systems.len();
+        let must_run_before = |first: usize, second: usize| {
+            let (a, b) = (&self.systems[first], &self.systems[second]);
+            a.phase == b.phase && (b.after.contains(&a.name) || a.before.contains(&b.name))
+        };
+
+        // The place of a system is the earliest of its own and those of all systems it has to run before, directly or not.
+        let mut places: Vec<usize> = (0..count).collect();
+        for _ in 0..count {
+            let mut changed = false;
+            for first in 0..count {
+                for second in 0..count {
+                    if must_run_before(first, second) && places[second] < places[first] {
+                        places[first] = places[second];
+                        changed = true;
+                    }
+                }
+            }
+            if !changed {
+                break;
+            }
+        }
+
+        let mut order = Vec::with_capacity(count);
+        let mut placed: Vec<bool> = (0..count).map(|_| false).collect();
+        let mut phases: Vec<SystemPhase> = self.systems.iter().map(|system| system.phase).collect();
+        phases.sort();
+        phases.dedup();
+        for phase in phases {
+            loop {
+                let ready = (0..count)
+                    .filter(|candidate| {
+                        !placed[*candidate]
+                            && self.systems[*candidate].phase == phase
+                            && (0..count).all(|other| placed[other] || other == *candidate || !must_run_before(other, *candidate))
+                    })
+                    .min_by_key(|candidate| (places[*candidate], *candidate));
+                match ready {
+                    Some(index) => {
+                        placed[index] = true;
+                        order.push(index);
+                    },
+                    None => break,
+                }
+            }
+            if let Some(stuck) = (0..count).find(|index| !placed[*index] && self.systems[*index].phase == phase) {
+                panic!("Systems in phase {:?} have cyclic ordering constraints, including {:?}", phase, self.systems[stuck].name);
+            }
+        }
+        order
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::game_state::PlayMode;
+    use crate::game_state::systems::system_name::SystemName;
+    use super::{RunCondition, Schedule, SystemPhase};
+
+    #[test]
+    fn test_phases_and_constraints() {
+        let mut schedule = Schedule::new();
+        schedule.add(SystemName::RenderMeshes);
+        schedule.add(SystemName::ScoreFryingPan);
+        schedule.add(SystemName::GameMaster);
+        schedule.add(SystemName::RegisterCollider).before(SystemName::ScoreFryingPan);
+        schedule.add(SystemName::CameraUpdate).before(SystemName::RenderMeshes);
+
+        assert_eq!(
+            schedule.systems_to_run(SystemPhase::Simulation, PlayMode::Playing),
+            vec![SystemName::RegisterCollider, SystemName::ScoreFryingPan, SystemName::GameMaster]
+        );
+        assert_eq!(
+            schedule.systems_to_run(SystemPhase::Presentation, PlayMode::Playing),
+            vec![SystemName::CameraUpdate, SystemName::RenderMeshes]
+        );
+    }
+
+    #[test]
+    fn test_enable_and_run_conditions() {
+        let mut schedule = Schedule::new();
+        schedule.add(SystemName::DebugPhysics).disabled();
+        schedule.add(SystemName::PatatoControl).run_if(RunCondition::InPlayMode(PlayMode::Playing));
+
+        assert!(schedule.systems_to_run(SystemPhase::Presentation, PlayMode::Playing).is_empty());
+        schedule.set_enabled(&SystemName::DebugPhysics, true);
+        assert!(schedule.is_enabled(&SystemName::DebugPhysics));
+        assert_eq!(schedule.systems_to_run(SystemPhase::Presentation, PlayMode::Playing), vec![SystemName::DebugPhysics]);
+
+        assert!(schedule.systems_to_run(SystemPhase::Input, PlayMode::Hands).is_empty());
+        assert_eq!(schedule.systems_to_run(SystemPhase::Input, PlayMode::Playing), vec![SystemName::PatatoControl]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_cyclic_constraints() {
+        let mut schedule = Schedule::new();
+        schedule.add(SystemName::GameMaster).after(SystemName::ScoreFryingPan);
+        schedule.add(SystemName::ScoreFryingPan).after(SystemName::GameMaster);
+        schedule.systems_to_run(SystemPhase::Simulation, PlayMode::Playing);
+    }
+}
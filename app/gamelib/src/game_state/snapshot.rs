@@ -0,0 +1,779 @@
+use core::time::Duration;
+use hecs::EntityBuilder;
+use strum::IntoEnumIterator;
+#[cfg(feature = "wii")]
+use ogc_rs::prelude::Vec;
+#[cfg(not(feature = "wii"))]
+use std::vec::Vec;
+
+use crate::bytes::{ByteReader, ByteWriter};
+use crate::game_state::components::audio::Audio;
+use crate::game_state::components::game::*;
+use crate::game_state::components::light::Light;
+use crate::game_state::components::modulated::{Modulated, Modulation, ModulatorSource};
+use crate::game_state::components::motion::{Position, PreviousPosition, Rotation, Scale, Velocity};
+use crate::game_state::components::particles::ParticleEmitter;
+use crate::game_state::components::physics::SphereCollider;
+use crate::game_state::components::render::{Colour, MeshInstance};
+use crate::game_state::components::timeline::Timeline;
+use super::results::{MatchResults, Standing};
+use super::rules::{MatchRules, PotatoMix};
+use super::{GameState, PlayMode};
+use modulator::ModulatorEnv;
+
+/// Every snapshot starts with these bytes, followed by the format version.
+const SNAPSHOT_MAGIC: &[u8; 4] = b"GGJS";
+const SNAPSHOT_VERSION: u8 = 11;
+
+// Which components an entity has, as bit flags.
+const HAS_POSITION: u64 = 1 << 0;
+const HAS_PREVIOUS_POSITION: u64 = 1 << 1;
+const HAS_VELOCITY: u64 = 1 << 2;
+const HAS_ROTATION: u64 = 1 << 3;
+const HAS_MESH_INSTANCE: u64 = 1 << 4;
+const HAS_PLATFORM: u64 = 1 << 5;
+const HAS_CAMERA: u64 = 1 << 6;
+const HAS_ANIMATION: u64 = 1 << 7;
+const HAS_CONTROLLER_ASSIGNMENT: u64 = 1 << 8;
+const HAS_FRY_ASSIGNMENT: u64 = 1 << 9;
+const HAS_SPHERE_COLLIDER: u64 = 1 << 10;
+const HAS_AUDIO: u64 = 1 << 11;
+const HAS_PLAYER: u64 = 1 << 12;
+const HAS_POTATO: u64 = 1 << 13;
+const HAS_BOT: u64 = 1 << 14;
+const HAS_TIMELINE: u64 = 1 << 15;
+const HAS_MODULATED: u64 = 1 << 16;
+const HAS_SCALE: u64 = 1 << 17;
+const HAS_LIGHT: u64 = 1 << 18;
+const HAS_PARTICLE_EMITTER: u64 = 1 << 19;
+
+/**
+ * Reasons why a snapshot could not be restored.
+ */
+#[derive(Debug, PartialEq)]
+pub enum SnapshotError {
+    /// The bytes do not start with the snapshot header.
+    NotASnapshot,
+    /// The snapshot was written by an unknown version of the format.
+    UnsupportedVersion(u8),
+    /// The snapshot ends halfway through.
+    Truncated,
+    /// The snapshot contains something this version of the game does not know, like an unknown mesh.
+    Corrupt,
+}
+
+/**
+ * Serializes all components gamelib knows about, the play mode and the match rules and time, into bytes.
+ *
+ * Entities are stored without their id, as entities do not refer to each other.
+ * Systems and servers are not part of the snapshot: it is restored into a state built by the same `GameStateName`.
+ */
+pub fn save(state: &GameState) -> Vec<u8> {
+    let mut writer = ByteWriter::new();
+    writer.write_bytes(SNAPSHOT_MAGIC);
+    writer.write_u8(SNAPSHOT_VERSION);
+    write_enum(&mut writer, &state.playmode);
+    write_rules(&mut writer, &state.rules);
+    write_results(&mut writer, &state.results);
+    writer.write_varint(state.match_time.as_nanos() as u64);
+    writer.write_varint(state.lobby_time.as_nanos() as u64);
+    writer.write_varint(state.world.len() as u64);
+    let mut query = state.world.query::<SavedComponents>();
+    for (_entity, components) in query.iter() {
+        write_entity(&mut writer, components);
+    }
+    writer.into_bytes()
+}
+
+/**
+ * Replaces all entities of the state by those in the snapshot, and restores the play mode and match.
+ *
+ * When the state has servers, the physics world is rebuilt as well:
+ * every collider gets a new body, at rest at the position of its entity.
+ */
+pub fn restore(state: &mut GameState, bytes: &[u8]) -> Result<(), SnapshotError> {
+    let mut reader = ByteReader::new(bytes);
+    if reader.read_bytes(SNAPSHOT_MAGIC.len()) != Some(&SNAPSHOT_MAGIC[..]) {
+        return Err(SnapshotError::NotASnapshot);
+    }
+    match reader.read_u8() {
+        Some(SNAPSHOT_VERSION) => (),
+        Some(version) => return Err(SnapshotError::UnsupportedVersion(version)),
+        None => return Err(SnapshotError::NotASnapshot),
+    }
+
+    // Read everything before touching the state, so a broken snapshot leaves it as it was.
+    let playmode: PlayMode = read_enum(&mut reader)?;
+    let rules = read_rules(&mut reader)?;
+    let results = read_results(&mut reader)?;
+    let match_time = Duration::from_nanos(reader.read_varint().ok_or(SnapshotError::Truncated)?);
+    let lobby_time = Duration::from_nanos(reader.read_varint().ok_or(SnapshotError::Truncated)?);
+    let entity_count = reader.read_varint().ok_or(SnapshotError::Truncated)?;
+    let mut builders = Vec::new();
+    for _ in 0..entity_count {
+        builders.push(read_entity(&mut reader)?);
+    }
+    if !reader.is_empty() {
+        return Err(SnapshotError::Corrupt);
+    }
+
+    state.world.clear();
+    for mut builder in builders {
+        state.world.spawn(builder.build());
+    }
+    // The modulators start over, around the same base values.
+    state.modulators = ModulatorEnv::new();
+    state.playmode = playmode;
+    state.rules = rules;
+    state.results = results;
+    state.match_time = match_time;
+    state.lobby_time = lobby_time;
+
+    if let Some(server_provider) = &state.server_provider {
+        let mut server_provider = server_provider.borrow_mut();
+        server_provider.physics_server.reset_world();
+        let mut query = state.world.query::<(&mut SphereCollider, &Position, Option<&Potato>)>();
+        let mut colliders: Vec<(&mut SphereCollider, &Position, Option<&Potato>)> = query.iter().map(|(_e, c)| c).collect();
+        let mut unregistered: Vec<(&mut SphereCollider, Option<&PotatoKind>)> = colliders.iter_mut()
+            .map(|(collider, _position, potato)| (&mut **collider, potato.map(|potato| &potato.kind)))
+            .collect();
+        server_provider.physics_server.register_collider(&mut unregistered);
+        for (collider, position, _potato) in colliders.iter() {
+            server_provider.physics_server.move_body_to(collider, position);
+        }
+    }
+    Ok(())
+}
+
+/**
+ * All components that are part of a snapshot. As they are all optional, every entity matches.
+ * They are split in groups, as a query can only hold so many components.
+ */
+type SavedComponents<'a> = (
+    (
+        Option<&'a Position>,
+        Option<&'a PreviousPosition>,
+        Option<&'a Velocity>,
+        Option<&'a Rotation>,
+        Option<&'a MeshInstance>,
+        Option<&'a Platform>,
+        Option<&'a Camera>,
+        Option<&'a Animation>,
+    ),
+    (
+        Option<&'a ControllerAssignment>,
+        Option<&'a FryAssignment>,
+        Option<&'a SphereCollider>,
+        Option<&'a Audio>,
+        Option<&'a Player>,
+        Option<&'a Potato>,
+        Option<&'a Bot>,
+        Option<&'a Timeline>,
+    ),
+    (
+        Option<&'a Modulated>,
+        Option<&'a Scale>,
+        Option<&'a Light>,
+        Option<&'a ParticleEmitter>,
+    ),
+);
+
+fn write_entity(writer: &mut ByteWriter, components: SavedComponents) {
+    let (
+        (
+            position,
+            previous_position,
+            velocity,
+            rotation,
+            mesh_instance,
+            platform,
+            camera,
+            animation,
+        ),
+        (
+            controller_assignment,
+            fry_assignment,
+            sphere_collider,
+            audio,
+            player,
+            potato,
+            bot,
+            timeline,
+        ),
+        (
+            modulated,
+            scale,
+            light,
+            particle_emitter,
+        ),
+    ) = components;
+
+    let mut mask = 0;
+    for (present, flag) in [
+        (position.is_some(), HAS_POSITION),
+        (previous_position.is_some(), HAS_PREVIOUS_POSITION),
+        (velocity.is_some(), HAS_VELOCITY),
+        (rotation.is_some(), HAS_ROTATION),
+        (mesh_instance.is_some(), HAS_MESH_INSTANCE),
+        (platform.is_some(), HAS_PLATFORM),
+        (camera.is_some(), HAS_CAMERA),
+        (animation.is_some(), HAS_ANIMATION),
+        (controller_assignment.is_some(), HAS_CONTROLLER_ASSIGNMENT),
+        (fry_assignment.is_some(), HAS_FRY_ASSIGNMENT),
+        (sphere_collider.is_some(), HAS_SPHERE_COLLIDER),
+        (audio.is_some(), HAS_AUDIO),
+        (player.is_some(), HAS_PLAYER),
+        (potato.is_some(), HAS_POTATO),
+        (bot.is_some(), HAS_BOT),
+        (timeline.is_some(), HAS_TIMELINE),
+        (modulated.is_some(), HAS_MODULATED),
+        (scale.is_some(), HAS_SCALE),
+        (light.is_some(), HAS_LIGHT),
+        (particle_emitter.is_some(), HAS_PARTICLE_EMITTER),
+    ] {
+        if present {
+            mask |= flag;
+        }
+    }
+    writer.write_varint(mask);
+
+    if let Some(position) = position {
+        write_vector(writer, position.x, position.y, position.z);
+    }
+    if let Some(previous) = previous_position {
+        write_vector(writer, previous.x, previous.y, previous.z);
+    }
+    if let Some(velocity) = velocity {
+        write_vector(writer, velocity.x, velocity.y, velocity.z);
+    }
+    if let Some(rotation) = rotation {
+        write_vector(writer, rotation.x, rotation.y, rotation.z);
+    }
+    if let Some(mesh_instance) = mesh_instance {
+        write_enum(writer, &mesh_instance.model_name);
+        write_colour(writer, &mesh_instance.tint);
+    }
+    if let Some(camera) = camera {
+        writer.write_bytes(&[camera.r, camera.g, camera.b]);
+        write_vector(writer, camera.up_x, camera.up_y, camera.up_z);
+        write_vector(writer, camera.lookat_x, camera.lookat_y, camera.lookat_z);
+    }
+    if let Some(animation) = animation {
+        writer.write_f32(animation.duration);
+        writer.write_f32(animation.past_time);
+        write_enum(writer, &animation.animation_type);
+        write_enum(writer, &animation.on_animation_finish);
+        write_vector(writer, animation.target_x, animation.target_y, animation.target_z);
+    }
+    if let Some(controller_assignment) = controller_assignment {
+        writer.write_varint(controller_assignment.id as u64);
+    }
+    if let Some(fry_assignment) = fry_assignment {
+        writer.write_varint(fry_assignment.id as u64);
+        writer.write_varint(fry_assignment.score as u64);
+    }
+    if let Some(collider) = sphere_collider {
+        writer.write_f32(collider.radius);
+        writer.write_u8(collider.gravity as u8);
+    }
+    if let Some(audio) = audio {
+        write_enum(writer, &audio.asset_name);
+        write_enum(writer, &audio.play_mode);
+        writer.write_u8(audio.is_processed as u8);
+        writer.write_f32(audio.volume);
+    }
+    if let Some(player) = player {
+        write_enum(writer, &player.colour);
+        writer.write_u8(player.ready as u8);
+    }
+    if let Some(potato) = potato {
+        write_enum(writer, &potato.kind);
+        writer.write_u8(potato.landed as u8);
+    }
+    if let Some(bot) = bot {
+        write_enum(writer, &bot.difficulty);
+        writer.write_f32(bot.thinking);
+    }
+    if let Some(timeline) = timeline {
+        write_enum(writer, &timeline.name);
+        writer.write_f32(timeline.time);
+        write_vector(writer, timeline.origin.0, timeline.origin.1, timeline.origin.2);
+    }
+    if let Some(modulated) = modulated {
+        writer.write_varint(modulated.modulations.len() as u64);
+        for modulation in modulated.modulations.iter() {
+            write_modulation(writer, modulation);
+        }
+    }
+    if let Some(scale) = scale {
+        write_vector(writer, scale.x, scale.y, scale.z);
+    }
+    if let Some(light) = light {
+        write_light(writer, light);
+    }
+    if let Some(particle_emitter) = particle_emitter {
+        write_particle_emitter(writer, particle_emitter);
+    }
+}
+
+fn read_entity(reader: &mut ByteReader) -> Result<EntityBuilder, SnapshotError> {
+    let mask = reader.read_varint().ok_or(SnapshotError::Truncated)?;
+    let mut builder = EntityBuilder::new();
+    if mask & HAS_POSITION != 0 {
+        let (x, y, z) = read_vector(reader)?;
+        builder.add(Position { x, y, z });
+    }
+    if mask & HAS_PREVIOUS_POSITION != 0 {
+        let (x, y, z) = read_vector(reader)?;
+        builder.add(PreviousPosition { x, y, z });
+    }
+    if mask & HAS_VELOCITY != 0 {
+        let (x, y, z) = read_vector(reader)?;
+        builder.add(Velocity { x, y, z });
+    }
+    if mask & HAS_ROTATION != 0 {
+        let (x, y, z) = read_vector(reader)?;
+        builder.add(Rotation { x, y, z });
+    }
+    if mask & HAS_MESH_INSTANCE != 0 {
+        let model_name = read_enum(reader)?;
+        builder.add(MeshInstance::tinted(model_name, read_colour(reader)?));
+    }
+    if mask & HAS_PLATFORM != 0 {
+        builder.add(Platform {});
+    }
+    if mask & HAS_CAMERA != 0 {
+        let colour = reader.read_bytes(3).ok_or(SnapshotError::Truncated)?;
+        let (r, g, b) = (colour[0], colour[1], colour[2]);
+        let (up_x, up_y, up_z) = read_vector(reader)?;
+        let (lookat_x, lookat_y, lookat_z) = read_vector(reader)?;
+        builder.add(Camera { r, g, b, up_x, up_y, up_z, lookat_x, lookat_y, lookat_z });
+    }
+    if mask & HAS_ANIMATION != 0 {
+        let duration = read_f32(reader)?;
+        let past_time = read_f32(reader)?;
+        let animation_type = read_enum(reader)?;
+        let on_animation_finish = read_enum(reader)?;
+        let (target_x, target_y, target_z) = read_vector(reader)?;
+        builder.add(Animation { duration, past_time, animation_type, on_animation_finish, target_x, target_y, target_z });
+    }
+    if mask & HAS_CONTROLLER_ASSIGNMENT != 0 {
+        builder.add(ControllerAssignment { id: read_usize(reader)? });
+    }
+    if mask & HAS_FRY_ASSIGNMENT != 0 {
+        let id = read_usize(reader)?;
+        let score = read_usize(reader)?;
+        builder.add(FryAssignment { id, score });
+    }
+    if mask & HAS_SPHERE_COLLIDER != 0 {
+        let radius = read_f32(reader)?;
+        let gravity = read_bool(reader)?;
+        // The physics body does not survive the snapshot, so it has to be registered again.
+        builder.add(SphereCollider { radius, gravity, body_index: 0, has_been_registered: false });
+    }
+    if mask & HAS_AUDIO != 0 {
+        let asset_name = read_enum(reader)?;
+        let play_mode = read_enum(reader)?;
+        let is_processed = read_bool(reader)?;
+        let volume = read_f32(reader)?;
+        builder.add(Audio { asset_name, play_mode, is_processed, volume });
+    }
+    if mask & HAS_PLAYER != 0 {
+        let colour = read_enum(reader)?;
+        let ready = read_bool(reader)?;
+        builder.add(Player { colour, ready });
+    }
+    if mask & HAS_POTATO != 0 {
+        let kind = read_enum(reader)?;
+        let landed = read_bool(reader)?;
+        builder.add(Potato { kind, landed });
+    }
+    if mask & HAS_BOT != 0 {
+        let difficulty = read_enum(reader)?;
+        let thinking = read_f32(reader)?;
+        builder.add(Bot { difficulty, thinking });
+    }
+    if mask & HAS_TIMELINE != 0 {
+        let name = read_enum(reader)?;
+        let time = read_f32(reader)?;
+        let origin = read_vector(reader)?;
+        builder.add(Timeline { name, time, origin });
+    }
+    if mask & HAS_MODULATED != 0 {
+        let count = read_usize(reader)?;
+        let mut modulations = Vec::new();
+        for _ in 0..count {
+            modulations.push(read_modulation(reader)?);
+        }
+        builder.add(Modulated { modulations });
+    }
+    if mask & HAS_SCALE != 0 {
+        let (x, y, z) = read_vector(reader)?;
+        builder.add(Scale { x, y, z });
+    }
+    if mask & HAS_LIGHT != 0 {
+        builder.add(read_light(reader)?);
+    }
+    if mask & HAS_PARTICLE_EMITTER != 0 {
+        builder.add(read_particle_emitter(reader)?);
+    }
+    Ok(builder)
+}
+
+fn write_rules(writer: &mut ByteWriter, rules: &MatchRules) {
+    writer.write_varint(rules.score_target as u64);
+    match rules.time_limit {
+        Some(time_limit) => {
+            writer.write_u8(1);
+            writer.write_varint(time_limit.as_nanos() as u64);
+        }
+        None => writer.write_u8(0),
+    }
+    writer.write_varint(rules.potato_count as u64);
+    writer.write_varint(rules.player_count as u64);
+    write_enum(writer, &rules.respawn);
+    writer.write_varint(rules.potato_mix.regular as u64);
+    writer.write_varint(rules.potato_mix.sweet as u64);
+    writer.write_varint(rules.potato_mix.golden as u64);
+    match &rules.bots {
+        Some(difficulty) => {
+            writer.write_u8(1);
+            write_enum(writer, difficulty);
+        }
+        None => writer.write_u8(0),
+    }
+}
+
+fn read_rules(reader: &mut ByteReader) -> Result<MatchRules, SnapshotError> {
+    let score_target = read_usize(reader)?;
+    let time_limit = if read_bool(reader)? {
+        Some(Duration::from_nanos(reader.read_varint().ok_or(SnapshotError::Truncated)?))
+    } else {
+        None
+    };
+    let potato_count = read_usize(reader)?;
+    let player_count = read_usize(reader)?;
+    let respawn = read_enum(reader)?;
+    let potato_mix = PotatoMix { regular: read_u32(reader)?, sweet: read_u32(reader)?, golden: read_u32(reader)? };
+    let bots = if read_bool(reader)? { Some(read_enum(reader)?) } else { None };
+    Ok(MatchRules { score_target, time_limit, potato_count, player_count, respawn, potato_mix, bots })
+}
+
+fn write_results(writer: &mut ByteWriter, results: &Option<MatchResults>) {
+    let results = match results {
+        Some(results) => results,
+        None => {
+            writer.write_u8(0);
+            return;
+        }
+    };
+    writer.write_u8(1);
+    write_rules(writer, &results.rules);
+    writer.write_varint(results.standings.len() as u64);
+    for standing in results.standings.iter() {
+        write_enum(writer, &standing.colour);
+        writer.write_varint(standing.score as u64);
+        writer.write_varint(standing.controller as u64);
+        match &standing.bot {
+            Some(difficulty) => {
+                writer.write_u8(1);
+                write_enum(writer, difficulty);
+            }
+            None => writer.write_u8(0),
+        }
+    }
+}
+
+fn read_results(reader: &mut ByteReader) -> Result<Option<MatchResults>, SnapshotError> {
+    if !read_bool(reader)? {
+        return Ok(None);
+    }
+    let rules = read_rules(reader)?;
+    let count = read_usize(reader)?;
+    let mut standings = Vec::new();
+    for _ in 0..count {
+        let colour = read_enum(reader)?;
+        let score = read_usize(reader)?;
+        let controller = read_usize(reader)?;
+        let bot = if read_bool(reader)? { Some(read_enum(reader)?) } else { None };
+        standings.push(Standing { colour, score, controller, bot });
+    }
+    // The standings were ranked when they were saved.
+    Ok(Some(MatchResults { standings, rules }))
+}
+
+/// The live modulator is not saved: only what is needed to build it again.
+fn write_modulation(writer: &mut ByteWriter, modulation: &Modulation) {
+    write_enum(writer, &modulation.field);
+    match modulation.source {
+        ModulatorSource::Wave { amplitude, frequency, phase } => {
+            writer.write_u8(0);
+            write_vector(writer, amplitude, frequency, phase);
+        }
+        ModulatorSource::Wander { min, max, smooth, pause } => {
+            writer.write_u8(1);
+            write_vector(writer, min, max, smooth);
+            writer.write_f32(pause);
+        }
+        ModulatorSource::Glide { min, max, speed, acceleration, pause } => {
+            writer.write_u8(2);
+            write_vector(writer, min, max, speed);
+            writer.write_f32(acceleration);
+            writer.write_f32(pause);
+        }
+        ModulatorSource::Steps { min, max, buckets, period, odds } => {
+            writer.write_u8(3);
+            write_vector(writer, min, max, period);
+            writer.write_f32(odds);
+            writer.write_varint(buckets as u64);
+        }
+    }
+    match modulation.base {
+        Some(base) => {
+            writer.write_u8(1);
+            writer.write_f32(base);
+        }
+        None => writer.write_u8(0),
+    }
+}
+
+fn read_modulation(reader: &mut ByteReader) -> Result<Modulation, SnapshotError> {
+    let field = read_enum(reader)?;
+    let source = match reader.read_u8().ok_or(SnapshotError::Truncated)? {
+        0 => {
+            let (amplitude, frequency, phase) = read_vector(reader)?;
+            ModulatorSource::Wave { amplitude, frequency, phase }
+        }
+        1 => {
+            let (min, max, smooth) = read_vector(reader)?;
+            ModulatorSource::Wander { min, max, smooth, pause: read_f32(reader)? }
+        }
+        2 => {
+            let (min, max, speed) = read_vector(reader)?;
+            ModulatorSource::Glide { min, max, speed, acceleration: read_f32(reader)?, pause: read_f32(reader)? }
+        }
+        3 => {
+            let (min, max, period) = read_vector(reader)?;
+            ModulatorSource::Steps { min, max, period, odds: read_f32(reader)?, buckets: read_usize(reader)? }
+        }
+        _ => return Err(SnapshotError::Corrupt),
+    };
+    let base = if read_bool(reader)? { Some(read_f32(reader)?) } else { None };
+    Ok(Modulation { field, source, base })
+}
+
+fn write_light(writer: &mut ByteWriter, light: &Light) {
+    match *light {
+        Light::Directional { direction, colour } => {
+            writer.write_u8(0);
+            write_vector(writer, direction.0, direction.1, direction.2);
+            write_colour(writer, &colour);
+        }
+        Light::Point { radius, colour } => {
+            writer.write_u8(1);
+            writer.write_f32(radius);
+            write_colour(writer, &colour);
+        }
+        Light::Ambient { colour } => {
+            writer.write_u8(2);
+            write_colour(writer, &colour);
+        }
+    }
+}
+
+fn read_light(reader: &mut ByteReader) -> Result<Light, SnapshotError> {
+    match reader.read_u8().ok_or(SnapshotError::Truncated)? {
+        0 => Ok(Light::Directional { direction: read_vector(reader)?, colour: read_colour(reader)? }),
+        1 => Ok(Light::Point { radius: read_f32(reader)?, colour: read_colour(reader)? }),
+        2 => Ok(Light::Ambient { colour: read_colour(reader)? }),
+        _ => Err(SnapshotError::Corrupt),
+    }
+}
+
+/// The live particles are not saved: they are gone within a second or two anyway.
+fn write_particle_emitter(writer: &mut ByteWriter, emitter: &ParticleEmitter) {
+    writer.write_f32(emitter.rate);
+    writer.write_varint(emitter.burst as u64);
+    match emitter.duration {
+        Some(duration) => {
+            writer.write_u8(1);
+            writer.write_f32(duration);
+        }
+        None => writer.write_u8(0),
+    }
+    writer.write_f32(emitter.lifetime);
+    write_vector(writer, emitter.direction.0, emitter.direction.1, emitter.direction.2);
+    write_vector(writer, emitter.spread, emitter.min_speed, emitter.max_speed);
+    writer.write_f32(emitter.gravity);
+    write_colour(writer, &emitter.start_colour);
+    write_colour(writer, &emitter.end_colour);
+    writer.write_f32(emitter.start_size);
+    writer.write_f32(emitter.end_size);
+    writer.write_varint(emitter.max_particles as u64);
+    writer.write_f32(emitter.elapsed);
+    writer.write_f32(emitter.pending);
+}
+
+fn read_particle_emitter(reader: &mut ByteReader) -> Result<ParticleEmitter, SnapshotError> {
+    let rate = read_f32(reader)?;
+    let burst = read_usize(reader)?;
+    let duration = if read_bool(reader)? { Some(read_f32(reader)?) } else { None };
+    let lifetime = read_f32(reader)?;
+    let direction = read_vector(reader)?;
+    let (spread, min_speed, max_speed) = read_vector(reader)?;
+    Ok(ParticleEmitter {
+        rate,
+        burst,
+        duration,
+        lifetime,
+        direction,
+        spread,
+        min_speed,
+        max_speed,
+        gravity: read_f32(reader)?,
+        start_colour: read_colour(reader)?,
+        end_colour: read_colour(reader)?,
+        start_size: read_f32(reader)?,
+        end_size: read_f32(reader)?,
+        max_particles: read_usize(reader)?,
+        elapsed: read_f32(reader)?,
+        pending: read_f32(reader)?,
+        particles: Vec::new(),
+    })
+}
+
+fn write_colour(writer: &mut ByteWriter, colour: &Colour) {
+    writer.write_bytes(&[colour.r, colour.g, colour.b, colour.a]);
+}
+
+fn read_colour(reader: &mut ByteReader) -> Result<Colour, SnapshotError> {
+    let bytes = reader.read_bytes(4).ok_or(SnapshotError::Truncated)?;
+    Ok(Colour { r: bytes[0], g: bytes[1], b: bytes[2], a: bytes[3] })
+}
+
+fn write_vector(writer: &mut ByteWriter, x: f32, y: f32, z: f32) {
+    writer.write_f32(x);
+    writer.write_f32(y);
+    writer.write_f32(z);
+}
+
+fn read_vector(reader: &mut ByteReader) -> Result<(f32, f32, f32), SnapshotError> {
+    Ok((read_f32(reader)?, read_f32(reader)?, read_f32(reader)?))
+}
+
+fn read_f32(reader: &mut ByteReader) -> Result<f32, SnapshotError> {
+    reader.read_f32().ok_or(SnapshotError::Truncated)
+}
+
+fn read_usize(reader: &mut ByteReader) -> Result<usize, SnapshotError> {
+    let value = reader.read_varint().ok_or(SnapshotError::Truncated)?;
+    usize::try_from(value).map_err(|_| SnapshotError::Corrupt)
+}
+
+fn read_u32(reader: &mut ByteReader) -> Result<u32, SnapshotError> {
+    let value = reader.read_varint().ok_or(SnapshotError::Truncated)?;
+    u32::try_from(value).map_err(|_| SnapshotError::Corrupt)
+}
+
+fn read_bool(reader: &mut ByteReader) -> Result<bool, SnapshotError> {
+    match reader.read_u8() {
+        Some(0) => Ok(false),
+        Some(1) => Ok(true),
+        Some(_) => Err(SnapshotError::Corrupt),
+        None => Err(SnapshotError::Truncated),
+    }
+}
+
+/// Enums are stored as their index in the enum, so they stay small.
+fn write_enum<T: IntoEnumIterator + PartialEq>(writer: &mut ByteWriter, value: &T) {
+    let index = T::iter().position(|variant| &variant == value).unwrap_or(0);
+    writer.write_varint(index as u64);
+}
+
+fn read_enum<T: IntoEnumIterator>(reader: &mut ByteReader) -> Result<T, SnapshotError> {
+    let index = read_usize(reader)?;
+    T::iter().nth(index).ok_or(SnapshotError::Corrupt)
+}
+
+#[cfg(test)]
+mod tests {
+    use core::time::Duration;
+    use crate::data_store::asset_name::AssetName;
+    use crate::data_store::textured_model_name::TexturedModelName;
+    use crate::game_state::components::audio::Audio;
+    use crate::game_state::components::game::{Animation, AnimationType, FryAssignment};
+    use crate::game_state::components::light::Light;
+    use crate::game_state::components::motion::{Position, Scale};
+    use crate::game_state::components::particles::ParticleEmitter;
+    use crate::game_state::components::render::{Colour, MeshInstance};
+    use crate::game_state::rules::MatchRules;
+    use crate::game_state::{GameState, PlayMode};
+    use crate::game_states::{GameStateFactory, GameStateName};
+    use super::{restore, save, SnapshotError};
+
+    #[test]
+    fn test_round_trip() {
+        let mut state = GameStateFactory::to_state(GameStateName::BouncingCubes, 10);
+        state.playmode = PlayMode::Finish;
+        state.rules = MatchRules::quick();
+        state.match_time = Duration::from_millis(1500);
+        state.lobby_time = Duration::from_secs(4);
+        state.world.spawn((FryAssignment { id: 3, score: 12 }, Position { x: 1.0, y: 2.0, z: 3.0 }));
+        state.world.spawn((MeshInstance::tinted(TexturedModelName::FryPanWhite, Colour::rgb(0xFF, 0x40, 0x00).with_alpha(0x80)), Scale::uniform(1.5)));
+        state.world.spawn((Light::Point { radius: 8.0, colour: Colour::rgb(0xFF, 0xC0, 0x80) }, Position { x: 0.0, y: 6.0, z: 0.0 }));
+        let mut splash = ParticleEmitter::splash(Colour::rgb(0xFF, 0xC8, 0x3C));
+        splash.elapsed = 0.25;
+        state.world.spawn((splash, Position { x: 0.0, y: -6.0, z: 0.0 }));
+        let bytes = save(&state);
+
+        let mut restored = GameState::new();
+        restored.world.spawn((Position { x: 0.0, y: 0.0, z: 0.0 },));
+        assert_eq!(restore(&mut restored, &bytes), Ok(()));
+
+        assert_eq!(restored.playmode, PlayMode::Finish);
+        assert_eq!(restored.rules, MatchRules::quick());
+        assert_eq!(restored.match_time, Duration::from_millis(1500));
+        assert_eq!(restored.lobby_time, Duration::from_secs(4));
+        assert_eq!(restored.world.len(), state.world.len());
+        let bubbles = restored.world.query::<(&MeshInstance, &Animation)>().iter()
+            .filter(|(_e, (mesh, animation))| mesh.model_name == TexturedModelName::OilBubble && animation.animation_type == AnimationType::Bubble)
+            .count();
+        assert_eq!(bubbles, 20);
+        let mut fry_pans = restored.world.query::<(&FryAssignment, &Position)>();
+        let (_e, (fry_pan, position)) = fry_pans.iter().next().unwrap();
+        assert_eq!((fry_pan.id, fry_pan.score), (3, 12));
+        assert_eq!((position.x, position.y, position.z), (1.0, 2.0, 3.0));
+        let mut tinted = restored.world.query::<(&MeshInstance, &Scale)>();
+        let (_e, (mesh, scale)) = tinted.iter().next().unwrap();
+        assert_eq!(mesh.tint, Colour { r: 0xFF, g: 0x40, b: 0x00, a: 0x80 });
+        assert_eq!(*scale, Scale::uniform(1.5));
+        let mut lights = restored.world.query::<(&Light, Option<&Position>)>();
+        assert_eq!(lights.iter().count(), state.world.query::<&Light>().iter().count());
+        let (_e, (light, _position)) = lights.iter()
+            .find(|(_e, (_light, position))| position.map(|position| (position.x, position.y, position.z)) == Some((0.0, 6.0, 0.0)))
+            .unwrap();
+        assert_eq!(*light, Light::Point { radius: 8.0, colour: Colour::rgb(0xFF, 0xC0, 0x80) });
+        let emitters: Vec<ParticleEmitter> = restored.world.query::<&ParticleEmitter>().iter().map(|(_e, emitter)| emitter.clone()).collect();
+        assert_eq!(emitters.len(), 5);
+        let splash = emitters.iter().find(|emitter| emitter.duration.is_some()).unwrap();
+        assert_eq!(splash.elapsed, 0.25);
+        assert_eq!(splash.end_colour, Colour::rgb(0xFF, 0xC8, 0x3C).with_alpha(0x00));
+        let music = restored.world.query::<&Audio>().iter().filter(|(_e, audio)| audio.asset_name == AssetName::DemoMusic).count();
+        assert_eq!(music, 1);
+
+        // Saving the restored state again gives the exact same bytes.
+        assert_eq!(save(&restored), bytes);
+    }
+
+    #[test]
+    fn test_broken_snapshot_leaves_state_alone() {
+        let state = GameStateFactory::to_state(GameStateName::BouncingCubes, 10);
+        let bytes = save(&state);
+
+        let mut other = GameState::new();
+        other.world.spawn((Position { x: 0.0, y: 0.0, z: 0.0 },));
+        assert_eq!(restore(&mut other, &bytes[..bytes.len() - 1]), Err(SnapshotError::Truncated));
+        assert_eq!(restore(&mut other, b"nope"), Err(SnapshotError::NotASnapshot));
+        assert_eq!(other.world.len(), 1);
+    }
+}
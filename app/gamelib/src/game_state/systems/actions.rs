@@ -1,13 +1,12 @@
-use ogc_rs::println;
-use ogc_rs::print;
+use alloc::vec::Vec;
 use crate::game_state::GameState;
 use crate::game_state::components::motion::Velocity;
 use crate::game_state::components::game::*;
-use rand::rngs::SmallRng;
 use rand::RngCore;
-use rand::SeedableRng;
 use crate::game_state::changes::controls::Direction;
 use crate::game_state::components::physics::SphereCollider;
+use crate::game_state::events::GameEvent;
+use crate::game_state::systems::system_name::SystemName;
 use crate::game_state::systems::system_name::SystemName::BounceBounds;
 use crate::game_states::GameStateName;
 
@@ -26,7 +25,10 @@ pub fn system_exit_action(state: &mut GameState) {
  */
 pub fn system_stop_action(state: &mut GameState) {
     for (_id, (velocity, controller_id)) in state.world.query_mut::<(&mut Velocity, & ControllerAssignment)>() {
-        let controller_state = state.changes.controls.get_wii_mote_control(controller_id);
+        let controller_state = match state.changes.controls.get_wii_mote_control(controller_id) {
+            Some(controller_state) => controller_state,
+            None => continue,
+        };
         if controller_state.one_button_down {
             velocity.x = 0.0;
             velocity.y = 0.0;
@@ -40,13 +42,15 @@ pub fn system_stop_action(state: &mut GameState) {
  */
 pub fn system_shake_action(state: &mut GameState) {
     for (_id, (velocity, controller_id)) in state.world.query_mut::<(&mut Velocity, & ControllerAssignment)>() {
-        let controller_state = state.changes.controls.get_wii_mote_control(controller_id);
+        let controller_state = match state.changes.controls.get_wii_mote_control(controller_id) {
+            Some(controller_state) => controller_state,
+            None => continue,
+        };
         match &controller_state.motion {
             None => (),
             Some(motion) => {
                 if motion.started {
-                    let mut small_rng = SmallRng::seed_from_u64(10u64);
-                    let c = small_rng.next_u32() as f32 / u32::MAX as f32 * 0.5 - 0.1;
+                    let c = state.rng.next_u32() as f32 / u32::MAX as f32 * 0.5 - 0.1;
                     match motion.direction {
                         Direction::Zp => velocity.y += c,
                         Direction::Zn => velocity.y -= c,
@@ -61,38 +65,88 @@ pub fn system_shake_action(state: &mut GameState) {
     }
 }
 
+/**
+ * Every motion started with a controller moves the potatoes owned by that controller (c.f. their `ControllerAssignment`).
+ */
 pub fn system_control_potato(state: &mut GameState) {
-    for (_id, (obj, controller_id)) in state.world.query_mut::<(&mut SphereCollider, & ControllerAssignment)>() {
-        // let controller_state = state.changes.controls.get_wii_mote_control(controller_id);
-        for i in 0..3 {
-            let player = ControllerAssignment { id: i };
-            match &state.changes.controls.get_wii_mote_control(&player).motion {
-                None => (),
-                Some(motion) => {
-                    if motion.started {
-                        let mut server_provider = state.server_provider.as_ref().unwrap().borrow_mut();
-                        server_provider.render_server.apply_movement(obj, motion.direction)
-                    }
-                }
+    let motions: Vec<(usize, Direction)> = state.events.this_step().iter()
+        .filter_map(|event| match event {
+            GameEvent::MotionStarted { controller, direction } => Some((*controller, *direction)),
+            _ => None,
+        })
+        .collect();
+    if motions.is_empty() {
+        return;
+    }
+
+    let mut server_provider = state.server_provider.as_ref().unwrap().borrow_mut();
+    for (_id, (potato, owner)) in state.world.query_mut::<(&SphereCollider, &ControllerAssignment)>() {
+        for (controller, direction) in motions.iter() {
+            if *controller == owner.id {
+                server_provider.physics_server.apply_movement(potato, *direction);
             }
         }
     }
 }
 
+/**
+ * Shows or hides the physics debug view when 'two' is pressed on any controller.
+ */
+pub fn system_debug_toggle(state: &mut GameState) {
+    let pressed = state.changes.controls.wii_mote_controls.iter().any(|control| control.two_button_down);
+    if pressed {
+        let enabled = state.is_system_enabled(&SystemName::DebugPhysics);
+        state.set_system_enabled(&SystemName::DebugPhysics, !enabled);
+    }
+}
+
+/**
+ * Pauses the game when 'plus' is pressed on any controller, by putting the pause state on top of this one.
+ */
+pub fn system_pause_action(state: &mut GameState) {
+    let pressed = state.changes.controls.wii_mote_controls.iter().any(|control| control.plus_button_down);
+    if pressed {
+        state.push_state(GameStateName::Pause);
+    }
+}
+
+/**
+ * Continues the paused game when 'plus' is pressed on any controller, by removing the pause state.
+ */
+pub fn system_resume_action(state: &mut GameState) {
+    let pressed = state.changes.controls.wii_mote_controls.iter().any(|control| control.plus_button_down);
+    if pressed {
+        state.pop_state();
+    }
+}
+
+/**
+ * Starts the level over when 'one' is pressed on any controller.
+ */
 pub fn system_reset_level(state: &mut GameState) {
-    for wii_mote_control in state.changes.controls.wii_mote_controls.iter() {
-        if wii_mote_control.one_button_down {
-            println!("RESETTTTT");
+    let pressed = state.changes.controls.wii_mote_controls.iter().any(|control| control.one_button_down);
+    if pressed {
+        {
             let mut server_provider = state.server_provider.as_ref().unwrap().borrow_mut();
-            server_provider.render_server.reset_world();
-            state.next_state = Some(GameStateName::BouncingCubes);
+            server_provider.physics_server.reset_world();
         }
+        state.replace_state(GameStateName::BouncingCubes);
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use alloc::rc::Rc;
+    use core::cell::RefCell;
     use crate::game_state::GameState;
+    use crate::game_state::changes::controls::{Direction, MotionControl};
+    use crate::game_state::components::game::{BOT_CONTROLLER_OFFSET, ControllerAssignment};
+    use crate::game_state::components::motion::{Position, Rotation, Velocity};
+    use crate::game_state::components::physics::SphereCollider;
+    use crate::game_state::events::GameEvent;
+    use crate::game_state::systems::physics::{system_physics_to_position, system_register_collider};
+    use crate::game_state::systems::system_name::SystemName;
+    use crate::servers::recording::recording_server_provider;
 
     #[test]
     fn test_exit() {
@@ -107,4 +161,72 @@ mod tests {
         super::system_exit_action(&mut state);
         assert_eq!(state.is_running, false);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_debug_toggle() {
+        let mut state = GameState::new();
+        state.add_system(SystemName::DebugPhysics).disabled();
+
+        super::system_debug_toggle(&mut state);
+        assert!(!state.is_system_enabled(&SystemName::DebugPhysics));
+
+        state.changes.controls.wii_mote_controls[2].two_button_down = true;
+        super::system_debug_toggle(&mut state);
+        assert!(state.is_system_enabled(&SystemName::DebugPhysics));
+        super::system_debug_toggle(&mut state);
+        assert!(!state.is_system_enabled(&SystemName::DebugPhysics));
+    }
+
+    #[test]
+    fn test_potatoes_follow_their_owner() {
+        let (server_provider, _recording) = recording_server_provider();
+        let mut state = GameState::new();
+        state.server_provider = Some(Rc::new(RefCell::new(server_provider)));
+        let spawn_potato = |state: &mut GameState, owner: usize| {
+            let collider = SphereCollider { radius: 1.0, gravity: true, body_index: 0, has_been_registered: false };
+            let position = Position { x: 0.0, y: 0.0, z: 0.0 };
+            let rotation = Rotation { x: 0.0, y: 0.0, z: 0.0 };
+            state.world.spawn((collider, ControllerAssignment { id: owner }, position, rotation))
+        };
+        let mine = spawn_potato(&mut state, 0);
+        let theirs = spawn_potato(&mut state, 1);
+        system_register_collider(&mut state);
+        system_physics_to_position(&mut state);
+        let x_of = |state: &GameState, potato| state.world.query_one::<&Position>(potato).unwrap().get().unwrap().x;
+        let (mine_before, theirs_before) = (x_of(&state, mine), x_of(&state, theirs));
+
+        state.events.emit(GameEvent::MotionStarted { controller: 1, direction: Direction::Xp });
+        super::system_control_potato(&mut state);
+        state.server_provider.as_ref().unwrap().borrow_mut().physics_server.world_step();
+        system_physics_to_position(&mut state);
+
+        assert_eq!(x_of(&state, mine), mine_before);
+        assert!(x_of(&state, theirs) < theirs_before);
+    }
+
+    #[test]
+    fn test_actions_skip_bots() {
+        let mut state = GameState::new();
+        let spawn_potato = |state: &mut GameState, owner: usize| {
+            state.world.spawn((Velocity { x: 1.0, y: 1.0, z: 1.0 }, ControllerAssignment { id: owner }))
+        };
+        let players = spawn_potato(&mut state, 0);
+        let bots = spawn_potato(&mut state, BOT_CONTROLLER_OFFSET + 1);
+        let velocity_of = |state: &GameState, potato| {
+            let mut query = state.world.query_one::<&Velocity>(potato).unwrap();
+            let velocity = query.get().unwrap();
+            (velocity.x, velocity.y, velocity.z)
+        };
+
+        // Shaking moves the potato of the player, but the potato of the bot has no Wii Remote to follow.
+        state.changes.controls.wii_mote_controls[0].motion = Some(MotionControl { direction: Direction::Xp, started: true, ended: false });
+        super::system_shake_action(&mut state);
+        assert_ne!(velocity_of(&state, players), (1.0, 1.0, 1.0));
+        assert_eq!(velocity_of(&state, bots), (1.0, 1.0, 1.0));
+
+        state.changes.controls.wii_mote_controls[0].one_button_down = true;
+        super::system_stop_action(&mut state);
+        assert_eq!(velocity_of(&state, players), (0.0, 0.0, 0.0));
+        assert_eq!(velocity_of(&state, bots), (1.0, 1.0, 1.0));
+    }
+}
@@ -1,4 +1,7 @@
+use crate::data_store::asset_name::AssetName;
 use crate::game_state::{GameState, components::audio::Audio};
+use crate::game_state::events::GameEvent;
+use crate::servers::audio::PlayMode;
 use hecs::Entity;
 #[cfg(feature = "wii")]
 use ogc_rs::prelude::Vec;
@@ -6,7 +9,7 @@ use ogc_rs::prelude::Vec;
 use std::vec::Vec;
 
 /**
- * Main audio system that plays audio components.
+ * Main audio system that plays audio components, and the sound effects of the events of this frame.
  */
 pub fn system_play_audio(state: &mut GameState) {
     if state.changes.delta_time.is_zero() {
@@ -17,11 +20,21 @@ pub fn system_play_audio(state: &mut GameState) {
     let audios: Vec<(Entity, &mut Audio)> = query.iter().collect();
     let server_provider = state.server_provider.as_ref().unwrap().borrow_mut();
 
-    server_provider.audio_server.set_volume(100);
+    // The audio server has a single volume, so the loudest audio decides.
+    let volume = audios.iter().map(|(_entity, audio)| audio.volume).fold(None, |loudest: Option<f32>, volume| match loudest {
+        Some(loudest) if loudest >= volume => Some(loudest),
+        _ => Some(volume),
+    });
+    server_provider.audio_server.set_volume(volume.unwrap_or(100.0).clamp(0.0, 255.0) as u32);
     for (_entity, audio) in audios {
         if !audio.is_processed {
             server_provider.audio_server.play(&audio.asset_name, audio.play_mode.clone());
             audio.is_processed = true;
         }
     }
+
+    let bounced = state.events.this_frame().iter().any(|event| matches!(event, GameEvent::Bounced { .. }));
+    if bounced {
+        server_provider.audio_server.play(&AssetName::BoingSFX, PlayMode::OneTime);
+    }
 }
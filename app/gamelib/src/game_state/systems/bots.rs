@@ -0,0 +1,105 @@
+use alloc::vec::Vec;
+use rand::RngCore;
+use crate::game_state::GameState;
+use crate::game_state::changes::controls::Direction;
+use crate::game_state::components::game::{Bot, BotDifficulty, ControllerAssignment, FryAssignment, Player, Potato};
+use crate::game_state::components::motion::Position;
+use crate::game_state::events::GameEvent;
+
+/// The directions a bot can push its potatoes in, along the plate.
+const DIRECTIONS: [Direction; 4] = [Direction::Xp, Direction::Xn, Direction::Zp, Direction::Zn];
+
+/**
+ * Lets every bot push one of its potatoes towards its own fry pan now and then.
+ * A bot moves by starting a motion with its controller, so its potatoes move exactly like those of a player.
+ */
+pub fn system_bots(state: &mut GameState) {
+    let delta_time = state.changes.delta_time.as_secs_f32();
+    let mut moving: Vec<(usize, usize, BotDifficulty)> = Vec::new();
+    for (_id, (bot, player, controller)) in state.world.query_mut::<(&mut Bot, &Player, &ControllerAssignment)>() {
+        bot.thinking -= delta_time;
+        if bot.thinking <= 0.0 {
+            bot.thinking += bot.difficulty.think_time();
+            moving.push((controller.id, player.colour.fry_pan_id(), bot.difficulty));
+        }
+    }
+
+    for (controller, fry_pan_id, difficulty) in moving {
+        let target = match fry_pan_position(state, fry_pan_id) {
+            Some(target) => target,
+            None => continue,
+        };
+        let direction = match closest_potato(state, controller, &target) {
+            Some(potato) => direction_towards(&potato, &target),
+            None => continue,
+        };
+        let direction = if random(state) < difficulty.mistake_chance() {
+            DIRECTIONS[(random(state) * DIRECTIONS.len() as f32) as usize % DIRECTIONS.len()]
+        } else {
+            direction
+        };
+        state.events.emit(GameEvent::MotionStarted { controller, direction });
+    }
+}
+
+fn random(state: &mut GameState) -> f32 {
+    state.rng.next_u32() as f32 / u32::MAX as f32
+}
+
+fn fry_pan_position(state: &mut GameState, fry_pan_id: usize) -> Option<Position> {
+    state.world.query_mut::<(&FryAssignment, &Position)>().into_iter()
+        .find(|(_id, (fry_assignment, _position))| fry_assignment.id == fry_pan_id)
+        .map(|(_id, (_fry_assignment, position))| Position { x: position.x, y: position.y, z: position.z })
+}
+
+/**
+ * The position of the potato of the controller that is closest to the target, leaving out those that already landed.
+ */
+fn closest_potato(state: &mut GameState, controller: usize, target: &Position) -> Option<Position> {
+    let mut closest: Option<(f32, Position)> = None;
+    for (_id, (potato, owner, position)) in state.world.query_mut::<(&Potato, &ControllerAssignment, &Position)>() {
+        if potato.landed || owner.id != controller {
+            continue;
+        }
+        let distance = flat_distance(position, target);
+        if closest.as_ref().map_or(true, |(best, _position)| distance < *best) {
+            closest = Some((distance, Position { x: position.x, y: position.y, z: position.z }));
+        }
+    }
+    closest.map(|(_distance, position)| position)
+}
+
+fn flat_distance(a: &Position, b: &Position) -> f32 {
+    let x_dif = b.x - a.x;
+    let z_dif = b.z - a.z;
+    x_dif * x_dif + z_dif * z_dif
+}
+
+/**
+ * The direction that pushes a potato most towards the target, along the plate (c.f. `movement_for_direction`).
+ */
+fn direction_towards(from: &Position, to: &Position) -> Direction {
+    let x_dif = to.x - from.x;
+    let z_dif = to.z - from.z;
+    if x_dif * x_dif > z_dif * z_dif {
+        if x_dif < 0.0 { Direction::Xp } else { Direction::Xn }
+    } else {
+        if z_dif > 0.0 { Direction::Zp } else { Direction::Zn }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::game_state::changes::controls::Direction;
+    use crate::game_state::components::motion::Position;
+    use super::direction_towards;
+
+    #[test]
+    fn test_direction_towards() {
+        let potato = Position { x: 2.0, y: 0.0, z: 1.0 };
+        assert_eq!(direction_towards(&potato, &Position { x: -10.0, y: -10.0, z: 3.0 }), Direction::Xp);
+        assert_eq!(direction_towards(&potato, &Position { x: 12.0, y: -10.0, z: -3.0 }), Direction::Xn);
+        assert_eq!(direction_towards(&potato, &Position { x: 0.0, y: -10.0, z: 13.0 }), Direction::Zp);
+        assert_eq!(direction_towards(&potato, &Position { x: 4.0, y: -10.0, z: -13.0 }), Direction::Zn);
+    }
+}
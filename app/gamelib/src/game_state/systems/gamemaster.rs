@@ -2,15 +2,20 @@ use crate::game_state::components::render::MeshInstance;
 use crate::game_state::*;
 use crate::game_state::components::motion::*;
 use crate::game_state::components::game::*;
+use crate::data_store::asset_name::AssetName;
 use crate::data_store::textured_model_name::TexturedModelName;
+use crate::game_state::scene::load_scene;
+use crate::game_state::events::GameEvent;
+use crate::game_state::components::timeline::{Timeline, TimelineName, TimelineSignal};
+use crate::game_state::components::tween::{Easing, Tween, TweenProperty};
+use crate::game_state::rules::RespawnBehaviour;
+use crate::game_state::results::{MatchResults, Standing};
+use core::time::Duration;
+use strum::IntoEnumIterator;
 use alloc::vec::Vec;
 use alloc::vec;
 use hecs::{DynamicBundle, Entity, Component};
-use ogc_rs::print;
 use micromath::F32Ext;
-use rand::rngs::SmallRng;
-use rand::RngCore;
-use rand::SeedableRng;
 use crate::game_state::components::physics::SphereCollider;
 
 /**
@@ -22,63 +27,210 @@ pub fn system_moving_platform(state: &mut GameState) {
 
 pub fn system_gamemaster(state: &mut GameState) {
     system_animation(state);
+    system_timeline_signals(state);
     system_camera_movement(state);
     system_game_start(state);
     system_game_finish(state);
 }
 
+/// How long the lobby waits for someone to join before the bots show how the game is played.
+pub const ATTRACT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/**
+ * Lets players join with their Wii Remote before the match, and starts the countdown once one of them is ready.
+ * When nobody joins for a while, the bots play a match among themselves (attract mode),
+ * until someone presses 'A' to get back to the lobby.
+ */
 pub fn system_game_start(state: &mut GameState) {
     match state.playmode {
-        PlayMode::Playing | PlayMode::Hands | PlayMode::Finish => {}
+        PlayMode::Playing | PlayMode::Hands | PlayMode::Finish => {
+            let pressed = state.changes.controls.wii_mote_controls.iter().any(|control| control.a_button_down);
+            if pressed && is_attract_match(state) {
+                {
+                    let mut server_provider = state.server_provider.as_ref().unwrap().borrow_mut();
+                    server_provider.physics_server.reset_world();
+                }
+                state.replace_state(GameStateName::BouncingCubes);
+            }
+        }
         PlayMode::Selection => {
-            let tmp_position = Position{
-                x: 0.0,
-                y: 0.0,
-                z: 0.0,
-            };
-            let tmp_animation = Animation {
-                duration: 0.0,
-                past_time: 0.0,
-                animation_type: AnimationType::None,
-                on_animation_finish: OnAnimationFinish::Hand3,
-                target_x: 0.0,
-                target_y: 0.0,
-                target_z: 0.0,
-            };
-    
-            state.world.spawn((tmp_position, tmp_animation));
-            state.playmode = PlayMode::Hands; // TODO: BUTTON click
+            // After a rematch was asked for, the same players are back, and ready.
+            if let Some(results) = state.results.take() {
+                rejoin_players(state, &results);
+            }
+            if !system_lobby(state) && !lobby_timed_out(state) {
+                return;
+            }
+            state.lobby_time = Duration::ZERO;
+            remove_lobby_previews(state);
+
+            let hand_position = Position { x: 0.0, y: 0.0, z: 0.0 };
+            let hand_rotation = Rotation { x: 0.0, y: 90.0, z: 10.0 };
+            let hand_mesh = MeshInstance::new(TexturedModelName::HandThree);
+            let countdown = Timeline::new(TimelineName::Countdown, (0.0, 0.0, 0.0));
+            state.world.spawn((hand_position, hand_rotation, hand_mesh, countdown));
+            state.playmode = PlayMode::Hands;
         }
     }
 }
 
-pub fn system_game_finish(state: &mut GameState) {
-    let mut winner: bool = false;
-    for (_id, (fry, animation, pos)) in state.world.query_mut::<(&mut FryAssignment, &mut Animation, &mut Position)>() {
-        if fry.score > 20 {
-            state.playmode = PlayMode::Finish; // TODO: BUTTON click
-            animation.duration = 5.0;
-            animation.past_time = 0.0;
-            animation.animation_type = AnimationType::FryerWin;
-            animation.on_animation_finish= OnAnimationFinish::Restart;
-            animation.target_x = pos.x;
-            animation.target_y = pos.y+5.0;
-            animation.target_z = pos.z;            
-            winner = true;
+/**
+ * Pressing 'A' on a Wii Remote joins the match, with the first pan colour nobody has yet.
+ * Pressing it again once joined means the player is ready.
+ * At most as many players as the match rules allow can join.
+ *
+ * Returns whether any player is ready.
+ */
+pub fn system_lobby(state: &mut GameState) -> bool {
+    let mut players: Vec<(Entity, usize, PanColour)> = Vec::new();
+    for (id, (player, controller)) in state.world.query_mut::<(&Player, &ControllerAssignment)>() {
+        players.push((id, controller.id, player.colour));
+    }
+
+    let pressed: Vec<usize> = state.changes.controls.wii_mote_controls.iter().enumerate()
+        .filter(|(_controller, control)| control.a_button_down)
+        .map(|(controller, _control)| controller)
+        .collect();
+    for controller in pressed {
+        let joined = players.iter()
+            .find(|(_id, player_controller, _colour)| *player_controller == controller)
+            .map(|(id, _controller, _colour)| *id);
+        match joined {
+            Some(id) => {
+                if let Ok(player) = state.world.query_one_mut::<&mut Player>(id) {
+                    player.ready = true;
+                }
+            }
+            None if players.len() < state.rules.player_count => {
+                let colour = PanColour::iter()
+                    .find(|colour| players.iter().all(|(_id, _controller, taken)| taken != colour));
+                if let Some(colour) = colour {
+                    let id = spawn_player(state, controller, colour, players.len());
+                    players.push((id, controller, colour));
+                    state.events.emit(GameEvent::PlayerJoined { controller, colour });
+                }
+            }
+            None => {}
+        }
+    }
+
+    state.world.query_mut::<&Player>().into_iter().any(|(_id, player)| player.ready)
+}
+
+/**
+ * Whether nobody joined the lobby for `ATTRACT_TIMEOUT`, while the match rules have bots to show the game with.
+ */
+fn lobby_timed_out(state: &mut GameState) -> bool {
+    let nobody_joined = state.world.query_mut::<&Player>().into_iter().next().is_none();
+    if !nobody_joined || state.rules.bots.is_none() {
+        state.lobby_time = Duration::ZERO;
+        return false;
+    }
+    state.lobby_time += state.changes.delta_time;
+    state.lobby_time >= ATTRACT_TIMEOUT
+}
+
+/// Whether the match is played by bots only, c.f. `ATTRACT_TIMEOUT`.
+fn is_attract_match(state: &mut GameState) -> bool {
+    state.world.query_mut::<(&Player, Option<&Bot>)>().into_iter().all(|(_id, (_player, bot))| bot.is_some())
+}
+
+/**
+ * Spawns a player, showing their fry pan in front of the camera while the others are joining.
+ */
+fn spawn_player(state: &mut GameState, controller: usize, colour: PanColour, index: usize) -> Entity {
+    let player = Player { colour, ready: false };
+    let preview_mesh = MeshInstance::new(colour.model_name());
+    let preview_x = -9.0 + 6.0 * index as f32;
+    // The fry pan pops up from below, and keeps spinning until the match starts.
+    let preview_position = Position { x: preview_x, y: 0.0, z: 12.0 };
+    let preview_pop = Tween::eased(TweenProperty::Position, (preview_x, 0.0, 12.0), (preview_x, 4.0, 12.0), 0.6, Easing::BackOut);
+    let preview_rotation = Rotation { x: 0.0, y: 0.0, z: 0.0 };
+    let preview_spin = Timeline::new(TimelineName::Spin, (preview_x, 4.0, 12.0));
+    state.world.spawn((player, ControllerAssignment { id: controller }, preview_mesh, preview_position, preview_pop, preview_rotation, preview_spin))
+}
+
+/// The players stay, but their fry pans are on the plate from now on.
+fn remove_lobby_previews(state: &mut GameState) {
+    let mut players: Vec<Entity> = Vec::new();
+    for (id, _player) in state.world.query_mut::<&Player>() {
+        players.push(id);
+    }
+    for id in players {
+        let _ = state.world.remove_one::<MeshInstance>(id);
+        let _ = state.world.remove_one::<Position>(id);
+        let _ = state.world.remove_one::<Rotation>(id);
+        let _ = state.world.remove_one::<PreviousPosition>(id);
+        let _ = state.world.remove_one::<Timeline>(id);
+        let _ = state.world.remove_one::<Tween>(id);
+    }
+}
+
+/**
+ * Lets the human players of the last match join again, with the same fry pans and the same rules.
+ * The bots come back by themselves once the match starts.
+ */
+fn rejoin_players(state: &mut GameState, results: &MatchResults) {
+    state.rules = results.rules.clone();
+    let humans = results.standings.iter().filter(|standing| standing.bot.is_none());
+    for (index, standing) in humans.enumerate() {
+        let id = spawn_player(state, standing.controller, standing.colour, index);
+        if let Ok(player) = state.world.query_one_mut::<&mut Player>(id) {
+            player.ready = true;
         }
     }
+}
 
-    if winner {
-        let mut to_remove: Vec<Entity> = Vec::new();
+/**
+ * Ends the match once a fry pan reaches the score target, or when the match rules say the match is over otherwise:
+ * the time is up, or all potatoes are gone. In those cases the highest score wins.
+ */
+pub fn system_game_finish(state: &mut GameState) {
+    if state.playmode == PlayMode::Playing {
+        state.match_time += state.changes.delta_time;
+        let time_is_up = match state.rules.time_limit {
+            Some(time_limit) => state.match_time >= time_limit,
+            None => false,
+        };
+        let out_of_potatoes = state.rules.respawn == RespawnBehaviour::Never
+            && state.world.query_mut::<&SphereCollider>().into_iter().next().is_none();
+        let best_score = state.world.query_mut::<&FryAssignment>().into_iter()
+            .map(|(_id, fry)| fry.score)
+            .max()
+            .unwrap_or(0);
 
-        for (id, fry) in state.world.query_mut::<(&mut FryAssignment)>() {
-            if fry.score < 20 {
-                to_remove.push(id);
+        let mut winners: Vec<Entity> = Vec::new();
+        let mut celebrations: Vec<(Entity, Timeline)> = Vec::new();
+        for (id, (fry, pos)) in state.world.query_mut::<(&FryAssignment, &Position)>() {
+            let reached_target = fry.score >= state.rules.score_target;
+            let best_at_the_end = (time_is_up || out_of_potatoes) && fry.score == best_score;
+            if reached_target || best_at_the_end {
+                state.events.emit(GameEvent::MatchWon { fry_pan: id });
+                celebrations.push((id, Timeline::new(TimelineName::WinRise, (pos.x, pos.y, pos.z))));
+                winners.push(id);
             }
         }
 
-        for id in to_remove.into_iter() {
-            state.world.despawn(id);
+        if !winners.is_empty() {
+            state.playmode = PlayMode::Finish;
+            state.results = Some(match_results(state));
+            // The winners stop circling the plate, and celebrate instead.
+            for (id, celebration) in celebrations {
+                let grow = Tween::eased(TweenProperty::Scale, (1.0, 1.0, 1.0), (1.5, 1.5, 1.5), 1.0, Easing::ElasticOut);
+                let _ = state.world.remove_one::<Animation>(id);
+                let _ = state.world.insert(id, (celebration, Scale::ONE, grow));
+            }
+            let mut to_remove: Vec<Entity> = Vec::new();
+
+            for (id, _fry) in state.world.query_mut::<&FryAssignment>() {
+                if !winners.contains(&id) {
+                    to_remove.push(id);
+                }
+            }
+
+            for id in to_remove.into_iter() {
+                state.world.despawn(id);
+            }
         }
     }
 
@@ -93,15 +245,8 @@ pub fn system_camera_movement(state: &mut GameState) {
     }
 }
 
-pub fn lerp(a: f32, b: f32, t:f32) -> f32 {
-    return a + (b-a) * t; 
-}
-
 pub fn system_animation(state: &mut GameState) {
     let mut to_remove: Vec<Entity> = Vec::new();
-    let mut to_add: Vec<(Position, Rotation, Animation, MeshInstance)> = Vec::new();
-    let mut startPlaying: bool = false;
-    
 
     for (id, (pos, animation)) in state.world.query_mut::<(&mut Position, &mut Animation)>() {
         animation.past_time += state.changes.delta_time.as_secs_f32();
@@ -138,15 +283,6 @@ pub fn system_animation(state: &mut GameState) {
                 pos.x = (animation.past_time * 0.32 + 9.01).sin() * 10.0 + (animation.past_time * 1.15 + 0.03).sin() * 2.0 + (animation.past_time * 1.47 + 5.31).sin();
                 pos.z = (animation.past_time * 0.43 + 2.32).cos() * 10.0 + (animation.past_time * 1.19 + 0.43).cos() * 2.0 + (animation.past_time * 1.83 + 1.84).cos();
             }
-            AnimationType::HandIn | AnimationType::HandOut | AnimationType::FryerWin => {
-                let mut t = animation.past_time / animation.duration;
-                pos.x = lerp(pos.x, animation.target_x, t);
-                pos.y = lerp(pos.y, animation.target_y, t);
-                pos.z = lerp(pos.z, animation.target_z, t);
-            }
-            AnimationType::Hand => {
-                pos.y += state.changes.delta_time.as_secs_f32();
-            }
         }
         
         if animation.past_time >= animation.duration {
@@ -166,202 +302,332 @@ pub fn system_animation(state: &mut GameState) {
                     pos.z = tmp; 
                 }
                 OnAnimationFinish::Despawn => { to_remove.push(id) }
+            }
+        }
+    }
 
-                OnAnimationFinish::Hand3 => {
-                    let hand_mesh = MeshInstance { model_name: TexturedModelName::HandThree };
-                    let hand_position = Position{
-                        x: 0.0,
-                        y: 0.0,
-                        z: 0.0,
-                    };
-                    let hand_animation = Animation {
-                        duration: 1.5,
-                        past_time: 0.0,
-                        animation_type: AnimationType::HandIn,
-                        on_animation_finish: OnAnimationFinish::Hand2,
-                        target_x: 1.0,
-                        target_y: 15.0,
-                        target_z: 15.0,
-                    };
-                    let hand_rotation = Rotation { x: 0.0, y: 90.0, z: 10.0 };
-                    to_add.push((hand_position, hand_rotation, hand_animation, hand_mesh)); 
-                    to_remove.push(id); 
-                } 
-
-                OnAnimationFinish::Hand2 => { 
-                    print!("switch to hand 2");
-                    let hand_position = Position {
-                        x: animation.target_x, y: animation.target_y, z: animation.target_z,
-                    };
-                    let hand_rotation = Rotation {
-                        x: 0.0, y: 90.0, z: 0.0,
-                    };
-                    let hand_animation = Animation {
-                        duration: 1.0,
-                        past_time: 0.0,
-                        animation_type: AnimationType::None,
-                        on_animation_finish: OnAnimationFinish::Hand1,
-                        target_x: pos.x, target_y: pos.y, target_z: pos.z, 
-                    };
-                    let hand_mesh_instance = MeshInstance { model_name: TexturedModelName::HandTwo };
-                    to_add.push((hand_position, hand_rotation, hand_animation, hand_mesh_instance)); 
-                    to_remove.push(id); 
-                }
-
-                OnAnimationFinish::Hand1 => { 
-                    print!("switch to hand 1");
-                    let hand_position = Position {
-                        x: animation.target_x, y: animation.target_y, z: animation.target_z,
-                    };
-                    let hand_rotation = Rotation {
-                        x: 0.0, y: 90.0, z: 0.0,
-                    };
-                    let hand_animation = Animation {
-                        duration: 1.0,
-                        past_time: 0.0,
-                        animation_type: AnimationType::None,
-                        on_animation_finish: OnAnimationFinish::Hand0,
-                        target_x: pos.x, target_y: pos.y, target_z: pos.z, 
-                    };
-                    let hand_mesh_instance = MeshInstance { model_name: TexturedModelName::HandOne };
-                    to_add.push((hand_position, hand_rotation, hand_animation, hand_mesh_instance)); 
-                    to_remove.push(id); 
-                }
-
-                OnAnimationFinish::Hand0 => { 
-                    print!("switch to hand 0");
-                    let hand_position = Position {
-                        x: animation.target_x, y: animation.target_y, z: animation.target_z,
-                    };
-                    let hand_rotation = Rotation {
-                        x: 0.0, y: 90.0, z: 0.0,
-                    };
-                    let hand_animation = Animation {
-                        duration: 0.5,
-                        past_time: 0.0,
-                        animation_type: AnimationType::HandOut,
-                        on_animation_finish: OnAnimationFinish::Start,
-                        target_x: 0.0, target_y: pos.y -10.0, target_z: 40.0, 
-                    };
-                    let hand_mesh_instance = MeshInstance { model_name: TexturedModelName::HandFist };
-                    to_add.push((hand_position, hand_rotation, hand_animation, hand_mesh_instance)); 
-                    to_remove.push(id); 
-                }
+    for id in to_remove.into_iter() {
+        state.world.despawn(id);
+    }
+}
 
-                OnAnimationFinish::Start => { 
-                    print!("switch to Start");
-                    startPlaying = true;
-                    to_remove.push(id); 
-                }
+/**
+ * The final score of every fry pan that took part in the match, together with who played it.
+ */
+fn match_results(state: &mut GameState) -> MatchResults {
+    let mut players: Vec<(PanColour, usize, Option<BotDifficulty>)> = Vec::new();
+    for (_id, (player, controller, bot)) in state.world.query_mut::<(&Player, &ControllerAssignment, Option<&Bot>)>() {
+        players.push((player.colour, controller.id, bot.map(|bot| bot.difficulty)));
+    }
+    let mut standings: Vec<Standing> = Vec::new();
+    for (_id, fry) in state.world.query_mut::<&FryAssignment>() {
+        let player = players.iter().find(|(colour, _controller, _bot)| colour.fry_pan_id() == fry.id);
+        if let Some((colour, controller, bot)) = player {
+            standings.push(Standing { colour: *colour, score: fry.score, controller: *controller, bot: *bot });
+        }
+    }
+    MatchResults::new(standings, state.rules.clone())
+}
 
-                OnAnimationFinish::Restart => {
+/**
+ * Starts the match when the countdown tells it to, and moves on to the results once the win has been celebrated.
+ */
+fn system_timeline_signals(state: &mut GameState) {
+    let signals: Vec<TimelineSignal> = state.events.this_step().iter()
+        .filter_map(|event| match event {
+            GameEvent::TimelineSignal { signal, .. } => Some(*signal),
+            _ => None,
+        })
+        .collect();
+    for signal in signals {
+        match signal {
+            TimelineSignal::StartMatch => start_match(state),
+            TimelineSignal::RestartMatch => {
+                {
                     let mut server_provider = state.server_provider.as_ref().unwrap().borrow_mut();
-                    server_provider.render_server.reset_world();
-                    state.next_state = Some(GameStateName::BouncingCubes);
+                    server_provider.physics_server.reset_world();
+                }
+                // After an attract match the lobby is back, waiting for someone to join.
+                if is_attract_match(state) {
+                    state.results = None;
+                    state.replace_state(GameStateName::BouncingCubes);
+                } else {
+                    state.replace_state(GameStateName::Results);
                 }
             }
         }
     }
+}
 
-    for id in to_remove.into_iter() {
-        state.world.despawn(id);
+/**
+ * Puts the fry pans and potatoes on the plate, and hands them out to the players and bots.
+ */
+fn start_match(state: &mut GameState) {
+    state.playmode = PlayMode::Playing;
+    state.match_time = Duration::ZERO;
+
+    load_scene(&mut state.world, AssetName::MatchScene, &mut state.rng).expect("Invalid match scene");
+    for _ in 0..state.rules.potato_count {
+        load_scene(&mut state.world, AssetName::PotatoScene, &mut state.rng).expect("Invalid potato scene");
     }
-    for comps in to_add.into_iter() {
-        state.world.spawn(comps);
+    mix_potatoes(state);
+    add_bots(state);
+    remove_unused_fry_pans(state);
+    hand_out_potatoes(state);
+}
+
+/**
+ * Turns the potatoes into the kinds the match rules ask for, each with its own look and size.
+ */
+pub fn mix_potatoes(state: &mut GameState) {
+    let potato_mix = state.rules.potato_mix;
+    for (_id, (potato, mesh, collider)) in state.world.query_mut::<(&mut Potato, &mut MeshInstance, &mut SphereCollider)>() {
+        // Once the body exists its shape is fixed, so only new potatoes can change kind.
+        if collider.has_been_registered {
+            continue;
+        }
+        potato.kind = potato_mix.pick(&mut state.rng);
+        mesh.model_name = potato.kind.model_name();
+        collider.radius = potato.kind.radius();
     }
+}
 
-    if startPlaying {
-        state.playmode = PlayMode::Playing;
+/**
+ * Divides the potatoes evenly over the players, in the order of their pan colours.
+ * Each player only moves their own potatoes.
+ */
+pub fn hand_out_potatoes(state: &mut GameState) {
+    let mut owners: Vec<(usize, usize)> = Vec::new();
+    for (_id, (player, controller)) in state.world.query_mut::<(&Player, &ControllerAssignment)>() {
+        owners.push((player.colour.fry_pan_id(), controller.id));
+    }
+    if owners.is_empty() {
+        return;
+    }
+    owners.sort();
+    let potatoes = state.world.query_mut::<(&SphereCollider, &mut ControllerAssignment)>().into_iter();
+    for (index, (_id, (_potato, owner))) in potatoes.enumerate() {
+        owner.id = owners[index % owners.len()].1;
+    }
+}
 
-        let offset: f32 = 30.0;
+/**
+ * Fills the fry pans nobody joined with with bots, as far as the match rules ask for them.
+ */
+pub fn add_bots(state: &mut GameState) {
+    let difficulty = match state.rules.bots {
+        Some(difficulty) => difficulty,
+        None => return,
+    };
+    let mut taken: Vec<PanColour> = Vec::new();
+    for (_id, player) in state.world.query_mut::<&Player>() {
+        taken.push(player.colour);
+    }
+    let free: Vec<PanColour> = PanColour::iter()
+        .filter(|colour| !taken.contains(colour))
+        .take(state.rules.player_count.saturating_sub(taken.len()))
+        .collect();
+    for colour in free {
+        let player = Player { colour, ready: true };
+        let controller = ControllerAssignment { id: BOT_CONTROLLER_OFFSET + colour.fry_pan_id() };
+        state.world.spawn((player, controller, Bot { difficulty, thinking: difficulty.think_time() }));
+    }
+}
 
-        let y = -10.0;
-        //Creating fryingpans
-        let fry_0_mesh = MeshInstance { model_name: TexturedModelName::FryPanBlack };
-        let fry_0_position = Position{ x: 0.0, y: y, z: 0.0,};
-        let fry_0_rotation = Rotation { x: 0.0, y: 0.0, z: 0.0 };
-        let fry_0_assignment = FryAssignment{id: 0, score: 0};
-        let fry_0_animation = Animation {
-            duration: 5.0,
-            past_time: 0.25 * offset,
-            animation_type: AnimationType::FryerSpin0,
-            on_animation_finish: OnAnimationFinish::Fryer,
-            target_x: 0.0,
-            target_y: y,
-            target_z: 0.0,
-        };
-        state.world.spawn((fry_0_mesh, fry_0_position, fry_0_rotation, fry_0_assignment, fry_0_animation));
+/**
+ * Only the fry pans of the players who joined take part in the match.
+ */
+fn remove_unused_fry_pans(state: &mut GameState) {
+    let mut taken: Vec<usize> = Vec::new();
+    for (_id, player) in state.world.query_mut::<&Player>() {
+        taken.push(player.colour.fry_pan_id());
+    }
+    let mut to_remove: Vec<Entity> = Vec::new();
+    for (id, fry) in state.world.query_mut::<&FryAssignment>() {
+        if !taken.contains(&fry.id) {
+            to_remove.push(id);
+        }
+    }
+    for id in to_remove.into_iter() {
+        state.world.despawn(id);
+    }
+}
 
-        let fry_1_mesh = MeshInstance { model_name: TexturedModelName::FryPanWhite };
-        let fry_1_position = Position{ x: 0.0, y: y, z: 0.0,};
-        let fry_1_rotation = Rotation { x: 0.0, y: 0.0, z: 0.0 };
-        let fry_1_assignment = FryAssignment{id: 1, score: 0};
-        let fry_1_animation = Animation {
-            duration: 5.0,
-            past_time: 0.5 * offset,
-            animation_type: AnimationType::FryerSpin0,
-            on_animation_finish: OnAnimationFinish::Fryer,
-            target_x: 0.0,
-            target_y: y,
-            target_z: 0.0,
-        };
-        state.world.spawn((fry_1_mesh, fry_1_position, fry_1_rotation, fry_1_assignment, fry_1_animation));
+#[cfg(test)]
+mod tests {
+    use alloc::rc::Rc;
+    use alloc::vec;
+    use alloc::vec::Vec;
+    use core::cell::RefCell;
+    use core::time::Duration;
+    use crate::game_state::components::game::{Animation, AnimationType, Bot, BotDifficulty, BOT_CONTROLLER_OFFSET, ControllerAssignment, FryAssignment, OnAnimationFinish, PanColour, Player};
+    use crate::game_state::components::motion::Position;
+    use crate::game_state::components::physics::SphereCollider;
+    use crate::game_state::events::GameEvent;
+    use crate::game_state::results::{MatchResults, Standing};
+    use crate::game_state::rules::MatchRules;
+    use crate::game_state::{GameState, PlayMode};
+    use crate::game_states::{GameStateName, Transition};
+    use crate::servers::recording::recording_server_provider;
+    use super::ATTRACT_TIMEOUT;
 
-        let fry_2_mesh = MeshInstance { model_name: TexturedModelName::FryPanBlue };
-        let fry_2_position = Position{ x: 0.0, y: y, z: 0.0,};
-        let fry_2_rotation = Rotation { x: 0.0, y: 0.0, z: 0.0 };
-        let fry_2_assignment = FryAssignment{id: 2, score: 0};
-        let fry_2_animation = Animation {
+    fn spawn_fry_pan(state: &mut GameState, id: usize, score: usize) -> hecs::Entity {
+        let animation = Animation {
             duration: 5.0,
-            past_time: 0.75 * offset,
+            past_time: 0.0,
             animation_type: AnimationType::FryerSpin0,
             on_animation_finish: OnAnimationFinish::Fryer,
             target_x: 0.0,
-            target_y: y,
+            target_y: -10.0,
             target_z: 0.0,
         };
-        state.world.spawn((fry_2_mesh, fry_2_position, fry_2_rotation, fry_2_assignment, fry_2_animation));
+        state.world.spawn((FryAssignment { id, score }, animation, Position { x: 0.0, y: -10.0, z: 0.0 }))
+    }
 
-        let fry_3_mesh = MeshInstance { model_name: TexturedModelName::FryPanRed };
-        let fry_3_position = Position{ x: 0.0, y: y, z: 0.0,};
-        let fry_3_rotation = Rotation { x: 0.0, y: 0.0, z: 0.0 };
-        let fry_3_assignment = FryAssignment{id: 3, score: 0};
-        let fry_3_animation = Animation {
-            duration: 5.0,
-            past_time: 1.0 * offset,
-            animation_type: AnimationType::FryerSpin0,
-            on_animation_finish: OnAnimationFinish::Fryer,
-            target_x: 0.0,
-            target_y: y,
-            target_z: 0.0,
-        };
-        state.world.spawn((fry_3_mesh, fry_3_position, fry_3_rotation, fry_3_assignment, fry_3_animation));
-
-        let mut small_rng = SmallRng::seed_from_u64(10u64);
-        for index in 0..20 {
-            const ROW_WIDTH: i32 = 10;
-            let pos_x: f32 = (index % ROW_WIDTH) as f32;
-            let pos_z: f32 = (index / ROW_WIDTH) as f32;
-
-            let position = Position {
-                x: pos_x,
-                y: 0.0,
-                z: pos_z,
-            };
-            let velocity = Velocity {
-                x: small_rng.next_u32() as f32 / u32::MAX as f32 * 0.1,
-                y: small_rng.next_u32() as f32 / u32::MAX as f32 * 0.1,
-                z: small_rng.next_u32() as f32 / u32::MAX as f32 * 0.1,
-            };
-            let rotation = Rotation { x: 0.0, y: 0.0, z: 0.0 };
-            
-            let mesh_instance = MeshInstance { model_name: TexturedModelName::Potato };
-            let sphere_collider = SphereCollider{radius: 1.0, gravity: true, body_index: 0, has_been_registered: false};
-            let controller_assignment = ControllerAssignment{
-                id: 0,
-            };
-
-            state.world.spawn((mesh_instance, position, velocity, rotation, sphere_collider, controller_assignment));
+    #[test]
+    fn test_time_limit_ends_match() {
+        let mut state = GameState::new();
+        state.rules = MatchRules::quick();
+        state.playmode = PlayMode::Playing;
+        state.changes.delta_time = Duration::from_secs(1);
+        spawn_fry_pan(&mut state, 0, 3);
+        let best = spawn_fry_pan(&mut state, 1, 5);
+
+        // Neither reaches the score target, so the match goes on until the time is up.
+        state.match_time = Duration::from_secs(80);
+        super::system_game_finish(&mut state);
+        assert_eq!(state.playmode, PlayMode::Playing);
+
+        state.match_time = Duration::from_secs(89);
+        super::system_game_finish(&mut state);
+        assert_eq!(state.playmode, PlayMode::Finish);
+        assert_eq!(state.events.this_step(), &[GameEvent::MatchWon { fry_pan: best }]);
+        assert_eq!(state.world.query::<&FryAssignment>().iter().count(), 1);
+
+        // A match is only won once.
+        super::system_game_finish(&mut state);
+        assert_eq!(state.events.this_step().len(), 1);
+    }
+
+    #[test]
+    fn test_potatoes_are_handed_out() {
+        let mut state = GameState::new();
+        state.world.spawn((Player { colour: PanColour::White, ready: true }, ControllerAssignment { id: 0 }));
+        state.world.spawn((Player { colour: PanColour::Black, ready: true }, ControllerAssignment { id: 3 }));
+        for _ in 0..5 {
+            let collider = SphereCollider { radius: 1.0, gravity: true, body_index: 0, has_been_registered: false };
+            state.world.spawn((collider, ControllerAssignment { id: 0 }));
         }
+        super::hand_out_potatoes(&mut state);
+
+        let owned_by = |state: &GameState, controller: usize| state.world.query::<(&SphereCollider, &ControllerAssignment)>().iter()
+            .filter(|(_id, (_potato, owner))| owner.id == controller)
+            .count();
+        assert_eq!(owned_by(&state, 3), 3);
+        assert_eq!(owned_by(&state, 0), 2);
+    }
+
+    #[test]
+    fn test_bots_fill_free_fry_pans() {
+        let mut state = GameState::new();
+        state.rules.player_count = 3;
+        state.world.spawn((Player { colour: PanColour::White, ready: true }, ControllerAssignment { id: 1 }));
+        super::add_bots(&mut state);
+
+        let mut bots: Vec<(usize, PanColour)> = state.world.query::<(&Bot, &Player, &ControllerAssignment)>().iter()
+            .map(|(_id, (_bot, player, controller))| (controller.id, player.colour))
+            .collect();
+        bots.sort_by_key(|(controller, _colour)| *controller);
+        assert_eq!(bots, vec![(BOT_CONTROLLER_OFFSET, PanColour::Black), (BOT_CONTROLLER_OFFSET + 2, PanColour::Blue)]);
+
+        state.rules.bots = None;
+        state.world.clear();
+        super::add_bots(&mut state);
+        assert_eq!(state.world.query::<&Bot>().iter().count(), 0);
+    }
+
+    #[test]
+    fn test_lobby() {
+        let mut state = GameState::new();
+        state.rules.player_count = 2;
+        super::system_game_start(&mut state);
+        assert_eq!(state.playmode, PlayMode::Selection);
+
+        // Players get the pan colours in the order they join, as far as the rules allow.
+        state.changes.controls.wii_mote_controls[2].a_button_down = true;
+        super::system_game_start(&mut state);
+        state.changes.controls.wii_mote_controls[2].a_button_down = false;
+        state.changes.controls.wii_mote_controls[0].a_button_down = true;
+        state.changes.controls.wii_mote_controls[3].a_button_down = true;
+        super::system_game_start(&mut state);
+        let mut players: Vec<(usize, PanColour)> = state.world.query::<(&Player, &ControllerAssignment)>().iter()
+            .map(|(_id, (player, controller))| (controller.id, player.colour))
+            .collect();
+        players.sort_by_key(|(controller, _colour)| *controller);
+        assert_eq!(players, vec![(0, PanColour::White), (2, PanColour::Black)]);
+        assert_eq!(state.events.this_step().len(), 2);
+        assert_eq!(state.playmode, PlayMode::Selection);
+
+        // Once a player is ready, the countdown starts.
+        state.changes.controls.wii_mote_controls[0].a_button_down = false;
+        state.changes.controls.wii_mote_controls[3].a_button_down = false;
+        state.changes.controls.wii_mote_controls[2].a_button_down = true;
+        super::system_game_start(&mut state);
+        assert_eq!(state.playmode, PlayMode::Hands);
+    }
+
+    #[test]
+    fn test_rematch() {
+        let mut state = GameState::new();
+        state.results = Some(MatchResults::new(vec![
+            Standing { colour: PanColour::Blue, score: 21, controller: 3, bot: None },
+            Standing { colour: PanColour::Red, score: 4, controller: BOT_CONTROLLER_OFFSET + 3, bot: Some(BotDifficulty::Hard) },
+        ], MatchRules::quick()));
+
+        // The human player is back with the same fry pan, and the countdown starts right away.
+        super::system_game_start(&mut state);
+        assert_eq!(state.playmode, PlayMode::Hands);
+        assert_eq!(state.rules, MatchRules::quick());
+        let players: Vec<(usize, PanColour, bool)> = state.world.query::<(&Player, &ControllerAssignment)>().iter()
+            .map(|(_id, (player, controller))| (controller.id, player.colour, player.ready))
+            .collect();
+        assert_eq!(players, vec![(3, PanColour::Blue, true)]);
+        assert!(state.results.is_none());
+    }
+
+    #[test]
+    fn test_attract_mode() {
+        let (server_provider, _recording) = recording_server_provider();
+        let mut state = GameState::new();
+        state.server_provider = Some(Rc::new(RefCell::new(server_provider)));
+        state.changes.delta_time = Duration::from_secs(1);
+
+        // While nobody joins, the lobby waits until the timeout, and then the countdown starts for the bots.
+        for _ in 1..ATTRACT_TIMEOUT.as_secs() {
+            super::system_game_start(&mut state);
+        }
+        assert_eq!(state.playmode, PlayMode::Selection);
+        super::system_game_start(&mut state);
+        assert_eq!(state.playmode, PlayMode::Hands);
+        assert_eq!(state.lobby_time, Duration::ZERO);
+
+        // Pressing 'A' brings the lobby back.
+        super::add_bots(&mut state);
+        super::system_game_start(&mut state);
+        assert!(state.transition.is_none());
+        state.changes.controls.wii_mote_controls[1].a_button_down = true;
+        super::system_game_start(&mut state);
+        assert_eq!(state.transition, Some(Transition::Replace(GameStateName::BouncingCubes)));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_no_attract_mode_once_joined() {
+        let mut state = GameState::new();
+        state.changes.delta_time = ATTRACT_TIMEOUT;
+        state.changes.controls.wii_mote_controls[0].a_button_down = true;
+        super::system_game_start(&mut state);
+        state.changes.controls.wii_mote_controls[0].a_button_down = false;
+        super::system_game_start(&mut state);
+        assert_eq!(state.playmode, PlayMode::Selection);
+    }
+}
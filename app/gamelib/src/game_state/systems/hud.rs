@@ -0,0 +1,80 @@
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::time::Duration;
+use crate::game_state::*;
+use crate::game_state::components::game::{FryAssignment, PanColour};
+use crate::game_state::components::render::Colour;
+use crate::servers::renderer::SCREEN_WIDTH;
+
+const MARGIN: f32 = 24.0;
+const PANEL_WIDTH: f32 = 96.0;
+const PANEL_HEIGHT: f32 = 40.0;
+const PANEL_SPACING: f32 = 8.0;
+const TEXT_SIZE: f32 = 16.0;
+const SCORE_SIZE: f32 = 24.0;
+
+/**
+ * Draws the scores of all fry pans, the score to reach and the time left on top of the match.
+ */
+pub fn system_render_hud(state: &mut GameState) {
+    match state.playmode {
+        PlayMode::Selection | PlayMode::Hands => return,
+        PlayMode::Playing | PlayMode::Finish => {}
+    }
+
+    let mut scores: Vec<(usize, usize)> = state.world.query_mut::<&FryAssignment>()
+        .into_iter()
+        .map(|(_e, fry_assignment)| (fry_assignment.id, fry_assignment.score))
+        .collect();
+    scores.sort();
+
+    let mut server_provider = state.server_provider.as_ref().unwrap().borrow_mut();
+    let render_server = &mut server_provider.render_server;
+
+    for (index, (id, score)) in scores.iter().enumerate() {
+        let colour = match PanColour::from_fry_pan_id(*id) {
+            Some(pan_colour) => pan_colour.colour(),
+            None => Colour::WHITE,
+        };
+        let x = MARGIN + index as f32 * (PANEL_WIDTH + PANEL_SPACING);
+        render_server.render_quad(x, MARGIN, PANEL_WIDTH, PANEL_HEIGHT, &Colour::BLACK.with_alpha(0x80));
+        render_server.render_quad(x, MARGIN, 8.0, PANEL_HEIGHT, &colour);
+        render_server.render_text(&format!("{}", score), x + 16.0, MARGIN + 8.0, SCORE_SIZE, &colour);
+    }
+
+    let target = format!("TARGET {}", state.rules.score_target);
+    let target_x = SCREEN_WIDTH - MARGIN - render_server.text_width(&target, TEXT_SIZE);
+    render_server.render_text(&target, target_x, MARGIN, TEXT_SIZE, &Colour::WHITE);
+
+    if let Some(time_limit) = state.rules.time_limit {
+        let time_left = format_time_left(time_limit.saturating_sub(state.match_time));
+        let time_x = SCREEN_WIDTH - MARGIN - render_server.text_width(&time_left, SCORE_SIZE);
+        render_server.render_text(&time_left, time_x, MARGIN + TEXT_SIZE + PANEL_SPACING, SCORE_SIZE, &Colour::WHITE);
+    }
+}
+
+/**
+ * Formats the time left as minutes and seconds, rounding up so the clock only shows 0:00 once time is up.
+ */
+fn format_time_left(time_left: Duration) -> String {
+    let mut seconds = time_left.as_secs();
+    if time_left.subsec_nanos() > 0 {
+        seconds += 1;
+    }
+    format!("{}:{:02}", seconds / 60, seconds % 60)
+}
+
+#[cfg(test)]
+mod tests {
+    use core::time::Duration;
+    use super::format_time_left;
+
+    #[test]
+    fn test_format_time_left() {
+        assert_eq!(format_time_left(Duration::from_secs(90)), "1:30");
+        assert_eq!(format_time_left(Duration::from_millis(59_001)), "1:00");
+        assert_eq!(format_time_left(Duration::from_millis(9_500)), "0:10");
+        assert_eq!(format_time_left(Duration::ZERO), "0:00");
+    }
+}
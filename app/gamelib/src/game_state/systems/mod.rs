@@ -5,4 +5,11 @@ mod audio;
 mod render;
 mod physics;
 mod gamemaster;
-mod score;
\ No newline at end of file
+mod score;
+mod hud;
+mod bots;
+mod timeline;
+mod tween;
+mod modulate;
+mod results;
+mod particles;
\ No newline at end of file
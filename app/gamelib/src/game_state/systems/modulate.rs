@@ -0,0 +1,130 @@
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use hashbrown::HashSet;
+use hecs::Entity;
+use modulator::ModulatorEnv;
+use crate::game_state::GameState;
+use crate::game_state::components::audio::Audio;
+use crate::game_state::components::game::Camera;
+use crate::game_state::components::modulated::{ModulatedField, Modulated};
+use crate::game_state::components::motion::{Position, Rotation};
+
+/**
+ * Advances all modulators by the time that passed, and puts their values in the fields they drive.
+ * Modulators are built for new modulations, seeded from `GameState::rng`, and let go of once their entity is gone.
+ */
+pub fn system_modulate(state: &mut GameState) {
+    let mut in_use: HashSet<String> = HashSet::new();
+    for (id, modulated) in state.world.query_mut::<&Modulated>() {
+        for (index, modulation) in modulated.modulations.iter().enumerate() {
+            let key = modulator_key(id, index);
+            if state.modulators.get(&key).is_none() {
+                state.modulators.take(&key, modulation.source.build(&mut state.rng));
+            }
+            in_use.insert(key);
+        }
+    }
+    let unused: Vec<String> = state.modulators.get_mods().keys()
+        .filter(|key| !in_use.contains(*key))
+        .cloned()
+        .collect();
+    for key in unused {
+        state.modulators.kill(&key);
+    }
+
+    state.modulators.advance(ModulatorEnv::<f32>::duration_to_micros(state.changes.delta_time));
+
+    for (id, (modulated, mut position, mut rotation, mut camera, mut audio)) in state.world.query_mut::<(
+        &mut Modulated,
+        Option<&mut Position>,
+        Option<&mut Rotation>,
+        Option<&mut Camera>,
+        Option<&mut Audio>,
+    )>() {
+        for (index, modulation) in modulated.modulations.iter_mut().enumerate() {
+            let field = match modulation.field {
+                ModulatedField::PositionX => position.as_deref_mut().map(|position| &mut position.x),
+                ModulatedField::PositionY => position.as_deref_mut().map(|position| &mut position.y),
+                ModulatedField::PositionZ => position.as_deref_mut().map(|position| &mut position.z),
+                ModulatedField::RotationX => rotation.as_deref_mut().map(|rotation| &mut rotation.x),
+                ModulatedField::RotationY => rotation.as_deref_mut().map(|rotation| &mut rotation.y),
+                ModulatedField::RotationZ => rotation.as_deref_mut().map(|rotation| &mut rotation.z),
+                ModulatedField::LookAtX => camera.as_deref_mut().map(|camera| &mut camera.lookat_x),
+                ModulatedField::LookAtY => camera.as_deref_mut().map(|camera| &mut camera.lookat_y),
+                ModulatedField::LookAtZ => camera.as_deref_mut().map(|camera| &mut camera.lookat_z),
+                ModulatedField::Volume => audio.as_deref_mut().map(|audio| &mut audio.volume),
+            };
+            if let Some(field) = field {
+                let base = *modulation.base.get_or_insert(*field);
+                *field = base + state.modulators.value(&modulator_key(id, index));
+            }
+        }
+    }
+}
+
+/// The key of the modulator of a modulation in `GameState::modulators`.
+fn modulator_key(entity: Entity, index: usize) -> String {
+    format!("{}/{}", entity.to_bits(), index)
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+    use alloc::vec::Vec;
+    use core::time::Duration;
+    use crate::game_state::GameState;
+    use crate::game_state::components::modulated::{Modulated, ModulatedField, Modulation, ModulatorSource};
+    use crate::game_state::components::motion::Position;
+    use super::system_modulate;
+
+    #[test]
+    fn test_wave_moves_around_base() {
+        let mut state = GameState::new();
+        let bubble = state.world.spawn((
+            Position { x: 0.0, y: -6.0, z: 0.0 },
+            Modulated { modulations: vec![Modulation {
+                field: ModulatedField::PositionY,
+                source: ModulatorSource::Wave { amplitude: 2.0, frequency: 1.0, phase: 0.0 },
+                base: None,
+            }] },
+        ));
+
+        // A quarter of a wave in, the bubble is at its highest.
+        state.changes.delta_time = Duration::from_millis(250);
+        system_modulate(&mut state);
+        assert!((state.world.query_one_mut::<&Position>(bubble).unwrap().y + 4.0).abs() < 0.001);
+
+        state.changes.delta_time = Duration::from_millis(500);
+        system_modulate(&mut state);
+        assert!((state.world.query_one_mut::<&Position>(bubble).unwrap().y + 8.0).abs() < 0.001);
+
+        // Once the entity is gone, so is its modulator.
+        state.world.despawn(bubble).unwrap();
+        system_modulate(&mut state);
+        assert!(state.modulators.get_mods().is_empty());
+    }
+
+    #[test]
+    fn test_random_modulators_follow_the_state_seed() {
+        let positions = |seed: u64| -> Vec<f32> {
+            let mut state = GameState::with_seed(seed);
+            let steam = state.world.spawn((
+                Position { x: 0.0, y: 0.0, z: 0.0 },
+                Modulated { modulations: vec![
+                    Modulation { field: ModulatedField::PositionX, source: ModulatorSource::Wander { min: -1.0, max: 1.0, smooth: 0.2, pause: 0.0 }, base: None },
+                    Modulation { field: ModulatedField::PositionY, source: ModulatorSource::Steps { min: -1.0, max: 1.0, buckets: 4, period: 1.0, odds: 0.5 }, base: None },
+                    Modulation { field: ModulatedField::PositionZ, source: ModulatorSource::Glide { min: -1.0, max: 1.0, speed: 2.0, acceleration: 4.0, pause: 0.1 }, base: None },
+                ] },
+            ));
+            state.changes.delta_time = Duration::from_millis(100);
+            (0..30).flat_map(|_| {
+                system_modulate(&mut state);
+                let position = state.world.query_one_mut::<&Position>(steam).unwrap();
+                [position.x, position.y, position.z]
+            }).collect()
+        };
+        assert_eq!(positions(4), positions(4));
+        assert_ne!(positions(4), positions(5));
+    }
+}
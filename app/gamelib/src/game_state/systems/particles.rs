@@ -0,0 +1,217 @@
+use alloc::vec::Vec;
+use core::f32::consts::PI;
+use hecs::Entity;
+use micromath::F32Ext;
+use rand::RngCore;
+use rand::rngs::SmallRng;
+use crate::game_state::GameState;
+use crate::game_state::components::game::{FryAssignment, PanColour};
+use crate::game_state::components::motion::Position;
+use crate::game_state::components::particles::{Particle, ParticleEmitter};
+use crate::game_state::components::render::Colour;
+use crate::game_state::events::GameEvent;
+use crate::servers::renderer::Billboard;
+
+/// The height of the surface of the oil, where potatoes that miss the fry pans splash.
+const OIL_SURFACE: f32 = -6.0;
+/// The colour of the oil that splashes up.
+const OIL_COLOUR: Colour = Colour::rgb(0xFF, 0xC8, 0x3C);
+
+/**
+ * Moves the particles of every emitter along, lets go of the ones that lived long enough, and emits new ones.
+ * Emitters that are done emitting are despawned along with their entity, once their last particle is gone.
+ */
+pub fn system_particles(state: &mut GameState) {
+    let delta_time = state.changes.delta_time.as_secs_f32();
+    let mut finished: Vec<Entity> = Vec::new();
+    for (id, (emitter, position)) in state.world.query_mut::<(&mut ParticleEmitter, &Position)>() {
+        let gravity = emitter.gravity;
+        for particle in emitter.particles.iter_mut() {
+            particle.age += delta_time;
+            particle.velocity.1 -= gravity * delta_time;
+            particle.position.0 += particle.velocity.0 * delta_time;
+            particle.position.1 += particle.velocity.1 * delta_time;
+            particle.position.2 += particle.velocity.2 * delta_time;
+        }
+        let lifetime = emitter.lifetime;
+        emitter.particles.retain(|particle| particle.age < lifetime);
+
+        if emitter.is_done() {
+            if emitter.particles.is_empty() {
+                finished.push(id);
+            }
+            continue;
+        }
+        let mut due = emitter.pending + emitter.rate * delta_time;
+        if emitter.elapsed == 0.0 {
+            due += emitter.burst as f32;
+        }
+        emitter.elapsed += delta_time;
+        let count = due as usize;
+        emitter.pending = due - count as f32;
+        for _ in 0..count {
+            if emitter.particles.len() >= emitter.max_particles {
+                break;
+            }
+            let particle = emit(emitter, position, &mut state.rng);
+            emitter.particles.push(particle);
+        }
+    }
+
+    for id in finished {
+        let _ = state.world.despawn(id);
+    }
+}
+
+/**
+ * A new particle at the position of the emitter, going off in a random direction within its spread.
+ */
+fn emit(emitter: &ParticleEmitter, position: &Position, rng: &mut SmallRng) -> Particle {
+    let forward = normalize(emitter.direction);
+    // Any two directions perpendicular to the forward direction, and to each other.
+    let helper = if forward.1.abs() < 0.99 { (0.0, 1.0, 0.0) } else { (1.0, 0.0, 0.0) };
+    let side = normalize(cross(forward, helper));
+    let other_side = cross(forward, side);
+
+    let tilt = emitter.spread.to_radians() * random(rng);
+    let turn = 2.0 * PI * random(rng);
+    let speed = emitter.min_speed + (emitter.max_speed - emitter.min_speed) * random(rng);
+    let (sin_tilt, cos_tilt) = (tilt.sin(), tilt.cos());
+    let (sin_turn, cos_turn) = (turn.sin(), turn.cos());
+    let direction = (
+        forward.0 * cos_tilt + (side.0 * cos_turn + other_side.0 * sin_turn) * sin_tilt,
+        forward.1 * cos_tilt + (side.1 * cos_turn + other_side.1 * sin_turn) * sin_tilt,
+        forward.2 * cos_tilt + (side.2 * cos_turn + other_side.2 * sin_turn) * sin_tilt,
+    );
+    Particle {
+        position: (position.x, position.y, position.z),
+        velocity: (direction.0 * speed, direction.1 * speed, direction.2 * speed),
+        age: 0.0,
+    }
+}
+
+/**
+ * Splashes the oil up where a potato falls in, and splashes a fry pan in its own colour when a potato lands in it.
+ */
+pub fn system_splashes(state: &mut GameState) {
+    let mut splashes: Vec<(Position, Colour)> = Vec::new();
+    for event in state.events.this_step() {
+        match *event {
+            GameEvent::PotatoScored { fry_pan, .. } => {
+                if let Ok((position, fry)) = state.world.query_one_mut::<(&Position, &FryAssignment)>(fry_pan) {
+                    let colour = PanColour::from_fry_pan_id(fry.id).map_or(Colour::WHITE, |colour| colour.colour());
+                    splashes.push((Position { x: position.x, y: position.y + 1.0, z: position.z }, colour));
+                }
+            }
+            GameEvent::PotatoFellInOil { potato } => {
+                if let Ok(position) = state.world.query_one_mut::<&Position>(potato) {
+                    splashes.push((Position { x: position.x, y: OIL_SURFACE, z: position.z }, OIL_COLOUR));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    for (position, colour) in splashes {
+        state.world.spawn((position, ParticleEmitter::splash(colour)));
+    }
+}
+
+/**
+ * Draws every particle as a billboard, at the size and colour for its age.
+ */
+pub fn system_render_particles(state: &mut GameState) {
+    let mut billboards: Vec<Billboard> = Vec::new();
+    for (_id, emitter) in state.world.query_mut::<&ParticleEmitter>() {
+        for particle in emitter.particles.iter() {
+            billboards.push(Billboard {
+                position: particle.position,
+                size: emitter.size_at(particle.age),
+                colour: emitter.colour_at(particle.age),
+            });
+        }
+    }
+    let mut server_provider = state.server_provider.as_ref().unwrap().borrow_mut();
+    server_provider.render_server.render_billboards(billboards);
+}
+
+fn random(rng: &mut SmallRng) -> f32 {
+    rng.next_u32() as f32 / u32::MAX as f32
+}
+
+fn cross(a: (f32, f32, f32), b: (f32, f32, f32)) -> (f32, f32, f32) {
+    (a.1 * b.2 - a.2 * b.1, a.2 * b.0 - a.0 * b.2, a.0 * b.1 - a.1 * b.0)
+}
+
+fn normalize(a: (f32, f32, f32)) -> (f32, f32, f32) {
+    let length = (a.0 * a.0 + a.1 * a.1 + a.2 * a.2).sqrt();
+    if length == 0.0 {
+        return (0.0, 1.0, 0.0);
+    }
+    (a.0 / length, a.1 / length, a.2 / length)
+}
+
+#[cfg(test)]
+mod tests {
+    use core::time::Duration;
+    use crate::game_state::GameState;
+    use crate::game_state::components::game::{FryAssignment, Potato, PotatoKind};
+    use crate::game_state::components::motion::Position;
+    use crate::game_state::components::particles::ParticleEmitter;
+    use crate::game_state::components::render::Colour;
+    use crate::game_state::events::GameEvent;
+    use super::{system_particles, system_splashes};
+
+    fn particle_count(state: &mut GameState) -> usize {
+        state.world.query_mut::<&ParticleEmitter>().into_iter().map(|(_id, emitter)| emitter.particles.len()).sum()
+    }
+
+    #[test]
+    fn test_rate_and_lifetime() {
+        let mut state = GameState::new();
+        let emitter = ParticleEmitter { spread: 30.0, min_speed: 2.0, max_speed: 4.0, ..ParticleEmitter::new(10.0, 0.95) };
+        let steam = state.world.spawn((Position { x: 0.0, y: 0.0, z: 0.0 }, emitter));
+        state.changes.delta_time = Duration::from_millis(100);
+
+        // Ten particles per second, which live for almost a second: after that, as many are emitted as die.
+        for _ in 0..10 {
+            system_particles(&mut state);
+        }
+        assert_eq!(particle_count(&mut state), 10);
+        for _ in 0..10 {
+            system_particles(&mut state);
+        }
+        assert_eq!(particle_count(&mut state), 10);
+
+        // All particles go up, within the spread.
+        let emitter = state.world.query_one_mut::<&ParticleEmitter>(steam).unwrap();
+        for particle in emitter.particles.iter() {
+            assert!(particle.position.1 >= 0.0);
+            assert!(particle.velocity.1 >= 2.0 * 30.0f32.to_radians().cos() - 0.001);
+        }
+        assert_eq!(emitter.colour_at(0.5), Colour::WHITE);
+    }
+
+    #[test]
+    fn test_splash_when_potato_falls_in_oil() {
+        let mut state = GameState::new();
+        let pan = state.world.spawn((Position { x: 5.0, y: -10.0, z: 0.0 }, FryAssignment { id: 2, score: 0 }));
+        let potato = state.world.spawn((Position { x: -3.0, y: -25.0, z: 4.0 }, Potato { kind: PotatoKind::Regular, landed: true }));
+        state.events.emit(GameEvent::PotatoFellInOil { potato });
+        state.events.emit(GameEvent::PotatoScored { fry_pan: pan, potato, points: 1 });
+        system_splashes(&mut state);
+        assert_eq!(state.world.query_mut::<&ParticleEmitter>().into_iter().count(), 2);
+
+        // A splash is a single burst, after which its entity is gone along with its drops.
+        state.changes.delta_time = Duration::from_millis(50);
+        state.events.clear();
+        system_particles(&mut state);
+        assert_eq!(particle_count(&mut state), 48);
+        for _ in 0..20 {
+            system_particles(&mut state);
+        }
+        assert_eq!(particle_count(&mut state), 0);
+        assert_eq!(state.world.query_mut::<&ParticleEmitter>().into_iter().count(), 0);
+        assert_eq!(state.world.len(), 2);
+    }
+}
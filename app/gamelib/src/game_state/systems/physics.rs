@@ -1,29 +1,114 @@
 use crate::game_state::GameState;
 use crate::game_state::components::physics::SphereCollider;
+use crate::game_state::components::game::Potato;
 use alloc::vec::Vec;
-use crate::game_state::components::motion::{Position, Rotation};
+use hecs::Entity;
+use crate::game_state::components::motion::{Position, PreviousPosition, Rotation};
 
 
 /**
- * Main render system that renders meshinstance components.
+ * Gives every new collider a body, which starts out at the position of its entity (when it has one).
  */
 pub fn system_register_collider(state: &mut GameState) {
-    let mut query = state.world.query::<&mut SphereCollider>();
-    let mut colliders = query.iter().map(|(_e, c)| c).collect();
+    let mut query = state.world.query::<(&mut SphereCollider, Option<&Position>, Option<&Potato>)>();
+    let mut new_colliders: Vec<(&mut SphereCollider, Option<&Position>, Option<&Potato>)> = query.iter()
+        .map(|(_e, components)| components)
+        .filter(|(collider, _position, _potato)| !collider.has_been_registered)
+        .collect();
+    if new_colliders.is_empty() {
+        return;
+    }
     let mut server_provider = state.server_provider.as_ref().unwrap().borrow_mut();
-    server_provider.render_server.register_collider(&mut colliders);
+    let mut colliders = new_colliders.iter_mut()
+        .map(|(collider, _position, potato)| (&mut **collider, potato.map(|potato| &potato.kind)))
+        .collect();
+    server_provider.physics_server.register_collider(&mut colliders);
+    for (collider, position, _potato) in new_colliders.iter() {
+        if let Some(position) = position {
+            server_provider.physics_server.move_body_to(collider, position);
+        }
+    }
 }
 
 pub fn system_physics_to_position(state: &mut GameState) {
     let mut query = state.world.query::<(&mut SphereCollider, &mut Position, &mut Rotation)>();
     let mut colliders = query.iter().map(|(_e, c)| c).collect();
     let mut server_provider = state.server_provider.as_ref().unwrap().borrow_mut();
-    server_provider.render_server.physics_to_position(&mut colliders);
+    server_provider.physics_server.physics_to_position(&mut colliders);
 }
 
+/**
+ * Puts the potatoes that landed back above the plate, following the spawn rule of the match rules,
+ * or removes them when the match rules say they do not respawn.
+ */
 pub fn system_teleport_potato(state: &mut GameState) {
-    let mut query = state.world.query::<(&mut SphereCollider, &mut Position, &mut Rotation)>();
-    let mut colliders = query.iter().map(|(_e, c)| c).collect();
+    let mut landed: Vec<Entity> = Vec::new();
+    for (id, potato) in state.world.query_mut::<&Potato>() {
+        if potato.landed {
+            landed.push(id);
+        }
+    }
+    if landed.is_empty() {
+        return;
+    }
+
     let mut server_provider = state.server_provider.as_ref().unwrap().borrow_mut();
-    server_provider.render_server.teleport_potato(&mut colliders);
+    for id in landed {
+        let spawn_position = match state.rules.respawn.spawn_position(&mut state.rng) {
+            Some(spawn_position) => spawn_position,
+            None => {
+                // The body goes along with the potato, so it does not keep colliding in the fry pan or sinking in the oil.
+                if let Ok(collider) = state.world.query_one_mut::<&SphereCollider>(id) {
+                    server_provider.physics_server.remove_body(collider);
+                }
+                let _ = state.world.despawn(id);
+                continue;
+            }
+        };
+        if let Ok((potato, collider, position, previous)) = state.world.query_one_mut::<(&mut Potato, &SphereCollider, &mut Position, Option<&mut PreviousPosition>)>(id) {
+            server_provider.physics_server.move_body_to(collider, &spawn_position);
+            // Jump straight to the new position, instead of sliding up from the oil.
+            if let Some(previous) = previous {
+                *previous = PreviousPosition { x: spawn_position.x, y: spawn_position.y, z: spawn_position.z };
+            }
+            *position = spawn_position;
+            potato.landed = false;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::rc::Rc;
+    use core::cell::RefCell;
+    use crate::game_state::GameState;
+    use crate::game_state::components::game::{Potato, PotatoKind};
+    use crate::game_state::components::motion::{Position, Rotation};
+    use crate::game_state::components::physics::SphereCollider;
+    use crate::game_state::rules::RespawnBehaviour;
+    use crate::servers::recording::recording_server_provider;
+    use super::{system_register_collider, system_teleport_potato};
+
+    #[test]
+    fn test_potato_that_does_not_respawn_takes_its_body_along() {
+        let (server_provider, _recording) = recording_server_provider();
+        let mut state = GameState::new();
+        state.server_provider = Some(Rc::new(RefCell::new(server_provider)));
+        state.rules.respawn = RespawnBehaviour::Never;
+        let mut spawn_potato = |landed: bool| {
+            let collider = SphereCollider { radius: 1.0, gravity: true, body_index: 0, has_been_registered: false };
+            let position = Position { x: 0.0, y: 0.0, z: 0.0 };
+            let rotation = Rotation { x: 0.0, y: 0.0, z: 0.0 };
+            state.world.spawn((collider, Potato { kind: PotatoKind::Regular, landed }, position, rotation))
+        };
+        spawn_potato(true);
+        spawn_potato(false);
+        system_register_collider(&mut state);
+        let body_count = |state: &GameState| state.server_provider.as_ref().unwrap().borrow().physics_server.body_count();
+        assert_eq!(body_count(&state), 2);
+
+        system_teleport_potato(&mut state);
+        assert_eq!(state.world.len(), 1);
+        assert_eq!(body_count(&state), 1);
+    }
 }
@@ -1,33 +1,63 @@
 use crate::game_state::GameState;
-use crate::game_state::components::motion::{Position, Rotation};
+use crate::game_state::components::motion::{Position, PreviousPosition, Rotation, Scale};
 use crate::game_state::components::physics::SphereCollider;
-use crate::game_state::components::render::MeshInstance;
+use crate::game_state::components::render::{Colour, MeshInstance};
 use crate::game_state::components::game::Camera;
+use crate::game_state::components::light::Light;
+use alloc::format;
 #[cfg(feature = "wii")]
 use ogc_rs::prelude::Vec;
 #[cfg(not(feature = "wii"))]
 use std::vec::Vec;
 
+/**
+ * The position to draw an entity at, in between its previous and current simulation step.
+ */
+fn interpolated_position(position: &Position, previous: Option<&PreviousPosition>, t: f32) -> Position {
+    match previous {
+        None => Position { x: position.x, y: position.y, z: position.z },
+        Some(previous) => Position {
+            x: previous.x + (position.x - previous.x) * t,
+            y: previous.y + (position.y - previous.y) * t,
+            z: previous.z + (position.z - previous.z) * t,
+        },
+    }
+}
+
 /**
  * Main render system that renders meshinstance components.
+ * The meshes are lit by the lights of the same game state, so an overlay does not light up the state below it.
  */
 pub fn system_render_meshes(state: &mut GameState) {
-    let mut query = state.world.query::<(&MeshInstance, &Position, &Rotation)>();
-    let mesh_instances: Vec<(&MeshInstance, &Position, &Rotation)> = query.iter().map(|(_e, (m, p, r))| (m, p, r)).collect();
+    let t = state.interpolation;
+    let mut light_query = state.world.query::<(&Light, Option<&Position>)>();
+    let lights: Vec<(&Light, Option<&Position>)> = light_query.iter().map(|(_e, light)| light).collect();
+    let mut query = state.world.query::<(&MeshInstance, &Position, Option<&PreviousPosition>, &Rotation, Option<&Scale>)>();
+    let positioned: Vec<(&MeshInstance, Position, &Rotation, Scale)> = query.iter()
+        .map(|(_e, (m, p, prev, r, s))| (m, interpolated_position(p, prev, t), r, s.copied().unwrap_or(Scale::ONE)))
+        .collect();
+    let mesh_instances: Vec<(&MeshInstance, &Position, &Rotation, &Scale)> = positioned.iter().map(|(m, p, r, s)| (*m, p, *r, s)).collect();
     let mut server_provider = state.server_provider.as_ref().unwrap().borrow_mut();
-    server_provider.render_server.render_meshes(mesh_instances);
+    server_provider.render_server.update_lights(lights);
+    state.render_stats = server_provider.render_server.render_meshes(mesh_instances);
 }
 
+/**
+ * Draws the physics colliders, and how many meshes were drawn and culled in the last frame.
+ */
 pub fn system_render_debug_physics(state: &mut GameState) {
     let mut query = state.world.query::<(&Position, &SphereCollider, &Rotation)>();
     let data = query.iter().map(|(_e, d)| d).collect();
     let mut server_provider = state.server_provider.as_ref().unwrap().borrow_mut();
     server_provider.render_server.render_debug(data);
+    let stats = format!("DRAWN {} CULLED {}", state.render_stats.drawn, state.render_stats.culled);
+    server_provider.render_server.render_text(&stats, 8.0, 8.0, 8.0, &Colour::WHITE);
 }
 
 pub fn system_camera_update(state: &mut GameState) {
-    for (id, (pos, camera)) in state.world.query_mut::<(&mut Position, &mut Camera)>() {
+    let t = state.interpolation;
+    for (id, (pos, prev, camera)) in state.world.query_mut::<(&mut Position, Option<&PreviousPosition>, &mut Camera)>() {
         let mut server_provider = state.server_provider.as_ref().unwrap().borrow_mut();
-        server_provider.render_server.update_camera(pos, camera);
+        server_provider.render_server.update_camera(&interpolated_position(pos, prev, t), camera);
     }
 }
\ No newline at end of file
@@ -0,0 +1,165 @@
+use alloc::format;
+use alloc::string::String;
+use crate::game_state::*;
+use crate::game_state::components::motion::{Position, Rotation};
+use crate::game_state::components::render::{Colour, MeshInstance};
+use crate::game_state::components::timeline::{Timeline, TimelineName};
+use crate::game_states::GameStateName;
+use crate::servers::renderer::{SCREEN_HEIGHT, SCREEN_WIDTH};
+
+/// Where the fry pans stand on the podium, in the order of the standings: the first in the middle, the others to either side.
+const PODIUM_X: [f32; 4] = [0.0, -6.0, 6.0, -12.0];
+/// How high each place stands on the podium.
+const PODIUM_Y: [f32; 4] = [3.0, 1.5, 0.5, -0.5];
+
+const MARGIN: f32 = 24.0;
+const ROW_WIDTH: f32 = 320.0;
+const ROW_HEIGHT: f32 = 32.0;
+const ROW_SPACING: f32 = 6.0;
+const TEXT_SIZE: f32 = 16.0;
+const MENU_SIZE: f32 = 12.0;
+const TITLE_SIZE: f32 = 32.0;
+const MENU: &str = "A REMATCH   1 NEW PLAYERS   HOME QUIT";
+
+/**
+ * Puts the fry pans of the last match on the podium, ranked by their score, as soon as the results are in.
+ * The play mode tells whether that happened already: it goes from `Selection` to `Finish`.
+ */
+pub fn system_podium(state: &mut GameState) {
+    if state.playmode != PlayMode::Selection {
+        return;
+    }
+    let results = match &state.results {
+        Some(results) => results.clone(),
+        None => return,
+    };
+    for ((index, standing), place) in results.standings.iter().enumerate().zip(results.places()) {
+        let x = PODIUM_X[index % PODIUM_X.len()];
+        let y = PODIUM_Y[(place - 1).min(PODIUM_Y.len() - 1)];
+        state.world.spawn((
+            MeshInstance::new(standing.colour.model_name()),
+            Position { x, y, z: 0.0 },
+            Rotation { x: 0.0, y: 0.0, z: 0.0 },
+            Timeline::new(TimelineName::Spin, (x, y, 0.0)),
+        ));
+    }
+    state.playmode = PlayMode::Finish;
+}
+
+/**
+ * Pressing 'A' on any Wii Remote plays a rematch with the same players, fry pans and rules.
+ * Pressing '1' goes back to the lobby, so other players can join, and 'Home' quits the game.
+ */
+pub fn system_results_menu(state: &mut GameState) {
+    let controls = &state.changes.controls.wii_mote_controls;
+    let rematch = controls.iter().any(|control| control.a_button_down);
+    let new_players = controls.iter().any(|control| control.one_button_down);
+    let quit = controls.iter().any(|control| control.home_button_down);
+    if rematch {
+        state.replace_state(GameStateName::BouncingCubes);
+    } else if new_players {
+        state.results = None;
+        state.replace_state(GameStateName::BouncingCubes);
+    } else if quit {
+        state.is_running = false;
+    }
+}
+
+/**
+ * Draws the ranking of the last match below the podium, and what can be done next.
+ */
+pub fn system_render_results(state: &mut GameState) {
+    let results = match &state.results {
+        Some(results) => results,
+        None => return,
+    };
+    let mut server_provider = state.server_provider.as_ref().unwrap().borrow_mut();
+    let render_server = &mut server_provider.render_server;
+
+    let title = "RESULTS";
+    let title_x = centered(render_server.text_width(title, TITLE_SIZE));
+    render_server.render_text(title, title_x, MARGIN, TITLE_SIZE, &Colour::WHITE);
+
+    let x = (SCREEN_WIDTH - ROW_WIDTH) / 2.0;
+    for ((index, standing), place) in results.standings.iter().enumerate().zip(results.places()) {
+        let y = 260.0 + index as f32 * (ROW_HEIGHT + ROW_SPACING);
+        render_server.render_quad(x, y, ROW_WIDTH, ROW_HEIGHT, &Colour::BLACK.with_alpha(0x80));
+        render_server.render_quad(x, y, 8.0, ROW_HEIGHT, &standing.colour.colour());
+        let player = match standing.bot {
+            Some(_) => "BOT",
+            None => "",
+        };
+        let row = format!("{:<4} {:<6} {:>3} {}", ordinal(place), format!("{:?}", standing.colour).to_uppercase(), standing.score, player);
+        render_server.render_text(&row, x + 16.0, y + (ROW_HEIGHT - TEXT_SIZE) / 2.0, TEXT_SIZE, &Colour::WHITE);
+    }
+
+    let menu_x = centered(render_server.text_width(MENU, MENU_SIZE));
+    render_server.render_text(MENU, menu_x, SCREEN_HEIGHT - MARGIN - MENU_SIZE, MENU_SIZE, &Colour::WHITE);
+}
+
+/// The x at which text of the given width is centered on the screen.
+fn centered(width: f32) -> f32 {
+    (SCREEN_WIDTH - width) / 2.0
+}
+
+fn ordinal(place: usize) -> String {
+    let suffix = match (place % 10, place % 100) {
+        (_, 11..=13) => "TH",
+        (1, _) => "ST",
+        (2, _) => "ND",
+        (3, _) => "RD",
+        _ => "TH",
+    };
+    format!("{}{}", place, suffix)
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+    use crate::data_store::textured_model_name::TexturedModelName;
+    use crate::game_state::{GameState, PlayMode};
+    use crate::game_state::components::game::PanColour;
+    use crate::game_state::components::motion::Position;
+    use crate::game_state::components::render::MeshInstance;
+    use crate::game_state::results::{MatchResults, Standing};
+    use crate::game_state::rules::MatchRules;
+    use crate::game_states::{GameStateName, Transition};
+    use super::{ordinal, system_podium, system_results_menu};
+
+    #[test]
+    fn test_podium_and_rematch() {
+        let mut state = GameState::new();
+        system_podium(&mut state);
+        assert_eq!(state.world.len(), 0);
+
+        state.results = Some(MatchResults::new(vec![
+            Standing { colour: PanColour::Blue, score: 4, controller: 0, bot: None },
+            Standing { colour: PanColour::Red, score: 21, controller: 1, bot: None },
+        ], MatchRules::standard()));
+        system_podium(&mut state);
+        system_podium(&mut state);
+        assert_eq!(state.playmode, PlayMode::Finish);
+        // The winner stands highest.
+        let mut pans: Vec<(f32, TexturedModelName)> = state.world.query_mut::<(&MeshInstance, &Position)>().into_iter()
+            .map(|(_id, (mesh, position))| (position.y, mesh.model_name.clone()))
+            .collect();
+        pans.sort_by(|a, b| b.0.total_cmp(&a.0));
+        assert_eq!(pans.len(), 2);
+        assert_eq!(pans[0].1, TexturedModelName::FryPanRed);
+
+        // A rematch keeps the results, so the same players can be set up again.
+        state.changes.controls.wii_mote_controls[2].a_button_down = true;
+        system_results_menu(&mut state);
+        assert_eq!(state.transition, Some(Transition::Replace(GameStateName::BouncingCubes)));
+        assert!(state.results.is_some());
+    }
+
+    #[test]
+    fn test_ordinal() {
+        assert_eq!(ordinal(1), "1ST");
+        assert_eq!(ordinal(2), "2ND");
+        assert_eq!(ordinal(3), "3RD");
+        assert_eq!(ordinal(4), "4TH");
+        assert_eq!(ordinal(11), "11TH");
+    }
+}
@@ -1,18 +1,97 @@
-use alloc::vec;
 use alloc::vec::Vec;
-use crate::game_state::components::game::FryAssignment;
-use crate::game_state::components::motion::{Position, Rotation};
-use crate::game_state::components::physics::SphereCollider;
-use crate::game_state::GameState;
-
+use hecs::Entity;
+use crate::game_state::components::game::{FryAssignment, Potato};
+use crate::game_state::components::motion::Position;
+use crate::game_state::{GameState, PlayMode};
+use crate::game_state::events::GameEvent;
+use crate::servers::physics::{FRY_PAN_RADIUS, RESPAWN_HEIGHT};
 
+/**
+ * Lands the potatoes that dropped below the plate since the last step.
+ *
+ * A potato that lands within the radius of a fry pan is credited to the closest one, exactly once, with the points of its kind,
+ * as it stays landed until it is put back on the plate (c.f. `system_teleport_potato`).
+ * Points are only given while the match is being played.
+ */
 pub fn system_score_frying_pans(state: &mut GameState) {
-    let mut potato_body_indexes = Vec::new();
-    for (_id, potato) in state.world.query_mut::<(&mut SphereCollider)>() {
-        potato_body_indexes.push(potato.body_index);
+    let mut landed: Vec<(Entity, Position, usize)> = Vec::new();
+    for (id, (potato, position)) in state.world.query_mut::<(&mut Potato, &Position)>() {
+        if !potato.landed && position.y < RESPAWN_HEIGHT {
+            potato.landed = true;
+            landed.push((id, Position { x: position.x, y: position.y, z: position.z }, potato.kind.points()));
+        }
+    }
+    if landed.is_empty() {
+        return;
+    }
+
+    let scoring = state.playmode == PlayMode::Playing;
+    for (potato, position, points) in landed {
+        match closest_fry_pan(state, &position) {
+            Some(fry_pan) if scoring => {
+                if let Ok(fry_assignment) = state.world.query_one_mut::<&mut FryAssignment>(fry_pan) {
+                    fry_assignment.score += points;
+                }
+                state.events.emit(GameEvent::PotatoScored { fry_pan, potato, points });
+            }
+            Some(_) => {}
+            None => state.events.emit(GameEvent::PotatoFellInOil { potato }),
+        }
     }
-    let mut server_provider = state.server_provider.as_ref().unwrap().borrow_mut();
-    for (_id, (position, fry_assignment)) in state.world.query_mut::<(&mut Position, &mut FryAssignment)>() {
-        server_provider.render_server.fry_pan_score_increase(position, fry_assignment, &potato_body_indexes);
+}
+
+/**
+ * The fry pan closest to the given position, as long as it is within its radius.
+ */
+fn closest_fry_pan(state: &mut GameState, position: &Position) -> Option<Entity> {
+    let mut closest: Option<(Entity, f32)> = None;
+    for (id, (pan_position, _fry_assignment)) in state.world.query_mut::<(&Position, &FryAssignment)>() {
+        let x_dif = position.x - pan_position.x;
+        let z_dif = position.z - pan_position.z;
+        let distance = x_dif * x_dif + z_dif * z_dif;
+        if distance < FRY_PAN_RADIUS * FRY_PAN_RADIUS && closest.map_or(true, |(_, best)| distance < best) {
+            closest = Some((id, distance));
+        }
+    }
+    closest.map(|(id, _distance)| id)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::game_state::components::game::{FryAssignment, Potato, PotatoKind};
+    use crate::game_state::components::motion::Position;
+    use crate::game_state::events::GameEvent;
+    use crate::game_state::{GameState, PlayMode};
+    use super::system_score_frying_pans;
+
+    #[test]
+    fn test_potato_scores_once_in_closest_pan() {
+        let mut state = GameState::new();
+        state.playmode = PlayMode::Playing;
+        let near = state.world.spawn((Position { x: 2.0, y: -10.0, z: 0.0 }, FryAssignment { id: 0, score: 0 }));
+        let far = state.world.spawn((Position { x: 8.0, y: -10.0, z: 0.0 }, FryAssignment { id: 1, score: 0 }));
+        let potato = state.world.spawn((Position { x: 0.0, y: -25.0, z: 0.0 }, Potato { kind: PotatoKind::Sweet, landed: false }));
+
+        // The potato lies below the plate for several steps, but only counts once.
+        for _ in 0..3 {
+            state.events.start_step();
+            system_score_frying_pans(&mut state);
+        }
+
+        assert_eq!(state.world.query_one_mut::<&FryAssignment>(near).unwrap().score, 2);
+        assert_eq!(state.world.query_one_mut::<&FryAssignment>(far).unwrap().score, 0);
+        assert_eq!(state.events.this_frame(), &[GameEvent::PotatoScored { fry_pan: near, potato, points: 2 }]);
+        assert!(state.world.query_one_mut::<&Potato>(potato).unwrap().landed);
+    }
+
+    #[test]
+    fn test_potato_misses_all_pans() {
+        let mut state = GameState::new();
+        state.playmode = PlayMode::Playing;
+        state.world.spawn((Position { x: 30.0, y: -10.0, z: 0.0 }, FryAssignment { id: 0, score: 0 }));
+        let potato = state.world.spawn((Position { x: 0.0, y: -25.0, z: 0.0 }, Potato { kind: PotatoKind::Regular, landed: false }));
+
+        system_score_frying_pans(&mut state);
+        assert_eq!(state.events.this_frame(), &[GameEvent::PotatoFellInOil { potato }]);
     }
-}
\ No newline at end of file
+}
@@ -1,4 +1,5 @@
 use crate::game_state::GameState;
+use crate::game_state::schedule::SystemPhase;
 use crate::game_state::systems::physics::*;
 use super::audio::system_play_audio;
 use super::motion::*;
@@ -6,6 +7,13 @@ use super::actions::*;
 use super::render::*;
 use super::gamemaster::*;
 use super::score::*;
+use super::hud::*;
+use super::bots::*;
+use super::timeline::*;
+use super::tween::*;
+use super::modulate::*;
+use super::results::*;
+use super::particles::*;
 
 /**
  * Enumerates all systems that exist in the project.
@@ -30,6 +38,20 @@ pub enum SystemName {
     ResetLevel,
     ScoreFryingPan,
     TeleportPotatoes,
+    DebugToggle,
+    PauseAction,
+    ResumeAction,
+    RenderHud,
+    BotControl,
+    Timeline,
+    Tween,
+    Modulate,
+    Podium,
+    ResultsMenu,
+    RenderResults,
+    Particles,
+    Splashes,
+    RenderParticles,
 }
 
 impl SystemName {
@@ -53,6 +75,57 @@ impl SystemName {
             SystemName::ResetLevel => &system_reset_level,
             SystemName::ScoreFryingPan => &system_score_frying_pans,
             SystemName::TeleportPotatoes => &system_teleport_potato,
+            SystemName::DebugToggle => &system_debug_toggle,
+            SystemName::PauseAction => &system_pause_action,
+            SystemName::ResumeAction => &system_resume_action,
+            SystemName::RenderHud => &system_render_hud,
+            SystemName::BotControl => &system_bots,
+            SystemName::Timeline => &system_timeline,
+            SystemName::Tween => &system_tween,
+            SystemName::Modulate => &system_modulate,
+            SystemName::Podium => &system_podium,
+            SystemName::ResultsMenu => &system_results_menu,
+            SystemName::RenderResults => &system_render_results,
+            SystemName::Particles => &system_particles,
+            SystemName::Splashes => &system_splashes,
+            SystemName::RenderParticles => &system_render_particles,
+        }
+    }
+
+    /// Returns the phase this system runs in, unless the game state says otherwise.
+    pub const fn default_phase(&self) -> SystemPhase {
+        match self {
+            SystemName::ExitAction
+            | SystemName::StopAction
+            | SystemName::ShakeAction
+            | SystemName::PatatoControl
+            | SystemName::ResetLevel
+            | SystemName::DebugToggle
+            | SystemName::PauseAction
+            | SystemName::ResumeAction
+            | SystemName::BotControl
+            | SystemName::ResultsMenu => SystemPhase::Input,
+            SystemName::GameMaster
+            | SystemName::MovingPlatform
+            | SystemName::IntegrateMotion
+            | SystemName::BounceBounds
+            | SystemName::RegisterCollider
+            | SystemName::ScoreFryingPan
+            | SystemName::Timeline
+            | SystemName::Tween
+            | SystemName::Modulate
+            | SystemName::Podium
+            | SystemName::Particles
+            | SystemName::Splashes => SystemPhase::Simulation,
+            SystemName::PhysicsToPosition
+            | SystemName::TeleportPotatoes => SystemPhase::PhysicsSync,
+            SystemName::DebugPhysics
+            | SystemName::CameraUpdate
+            | SystemName::PlayAudio
+            | SystemName::RenderMeshes
+            | SystemName::RenderHud
+            | SystemName::RenderResults
+            | SystemName::RenderParticles => SystemPhase::Presentation,
         }
     }
 }
\ No newline at end of file
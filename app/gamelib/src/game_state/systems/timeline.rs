@@ -0,0 +1,203 @@
+use alloc::vec::Vec;
+use hecs::Entity;
+use crate::data_store::textured_model_name::TexturedModelName;
+use crate::game_state::GameState;
+use crate::game_state::components::motion::{Position, Rotation};
+use crate::game_state::components::render::MeshInstance;
+use crate::game_state::components::timeline::{Keyframe, KeyframeChange, Timeline, TimelineEnd, TimelineName, TimelineSignal};
+use crate::game_state::events::GameEvent;
+
+/**
+ * Plays all timelines: moves, turns and reskins their entities along their keyframes,
+ * and emits the signals of the keyframes that were passed in this step.
+ */
+pub fn system_timeline(state: &mut GameState) {
+    let delta_time = state.changes.delta_time.as_secs_f32();
+    let mut signals: Vec<(Entity, TimelineSignal)> = Vec::new();
+    let mut finished: Vec<Entity> = Vec::new();
+    for (id, (timeline, mut position, mut rotation, mut mesh)) in state.world.query_mut::<(&mut Timeline, Option<&mut Position>, Option<&mut Rotation>, Option<&mut MeshInstance>)>() {
+        let before = timeline.time;
+        timeline.time += delta_time;
+        let duration = timeline.name.duration();
+        for signal in passed_signals(timeline.name, before, timeline.time) {
+            signals.push((id, signal));
+        }
+
+        if timeline.time >= duration {
+            match timeline.name.end() {
+                TimelineEnd::Hold => timeline.time = duration,
+                TimelineEnd::Loop if duration > 0.0 => {
+                    timeline.time -= duration;
+                    for signal in passed_signals(timeline.name, 0.0, timeline.time) {
+                        signals.push((id, signal));
+                    }
+                }
+                TimelineEnd::Loop => timeline.time = 0.0,
+                TimelineEnd::Despawn => {
+                    timeline.time = duration;
+                    finished.push(id);
+                }
+                TimelineEnd::Chain(next) => {
+                    // Leave the entity as this timeline ended, before the next one takes over.
+                    apply_keyframes(timeline, position.as_deref_mut(), rotation.as_deref_mut(), mesh.as_deref_mut());
+                    timeline.name = next;
+                    timeline.time = 0.0;
+                }
+            }
+        }
+        apply_keyframes(timeline, position, rotation, mesh);
+    }
+
+    for (entity, signal) in signals {
+        state.events.emit(GameEvent::TimelineSignal { entity, signal });
+    }
+    for id in finished {
+        let _ = state.world.despawn(id);
+    }
+}
+
+/**
+ * The signals of the keyframes after `from`, up to and including `to`.
+ */
+fn passed_signals(name: TimelineName, from: f32, to: f32) -> impl Iterator<Item = TimelineSignal> {
+    name.keyframes().iter().filter_map(move |keyframe| match keyframe.change {
+        KeyframeChange::Signal(signal) if keyframe.time > from && keyframe.time <= to => Some(signal),
+        _ => None,
+    })
+}
+
+/**
+ * Puts the entity where its timeline says it is at the current time.
+ * Only what the timeline has keyframes for is changed.
+ */
+fn apply_keyframes(timeline: &Timeline, position: Option<&mut Position>, rotation: Option<&mut Rotation>, mesh: Option<&mut MeshInstance>) {
+    let keyframes = timeline.name.keyframes();
+    if let (Some(position), Some((x, y, z))) = (position, sample(keyframes, timeline.time, |change| match change {
+        KeyframeChange::Position(x, y, z) => Some((*x, *y, *z)),
+        _ => None,
+    })) {
+        position.x = timeline.origin.0 + x;
+        position.y = timeline.origin.1 + y;
+        position.z = timeline.origin.2 + z;
+    }
+    if let (Some(rotation), Some((x, y, z))) = (rotation, sample(keyframes, timeline.time, |change| match change {
+        KeyframeChange::Rotation(x, y, z) => Some((*x, *y, *z)),
+        _ => None,
+    })) {
+        rotation.x = x;
+        rotation.y = y;
+        rotation.z = z;
+    }
+    if let (Some(mesh), Some(model_name)) = (mesh, current_mesh(keyframes, timeline.time)) {
+        mesh.model_name = model_name.clone();
+    }
+}
+
+/**
+ * The value at the given time, moving linearly from one keyframe to the next.
+ * Before the first keyframe it is the value of the first one, after the last keyframe the value of the last one.
+ */
+fn sample(keyframes: &[Keyframe], time: f32, value: impl Fn(&KeyframeChange) -> Option<(f32, f32, f32)>) -> Option<(f32, f32, f32)> {
+    let mut previous: Option<(f32, (f32, f32, f32))> = None;
+    for keyframe in keyframes {
+        let current = match value(&keyframe.change) {
+            Some(current) => current,
+            None => continue,
+        };
+        if keyframe.time >= time {
+            return match previous {
+                Some((previous_time, from)) if keyframe.time > previous_time => {
+                    let t = (time - previous_time) / (keyframe.time - previous_time);
+                    Some((
+                        from.0 + (current.0 - from.0) * t,
+                        from.1 + (current.1 - from.1) * t,
+                        from.2 + (current.2 - from.2) * t,
+                    ))
+                }
+                _ => Some(current),
+            };
+        }
+        previous = Some((keyframe.time, current));
+    }
+    previous.map(|(_time, value)| value)
+}
+
+/**
+ * The mesh of the last mesh keyframe at or before the given time.
+ */
+fn current_mesh(keyframes: &[Keyframe], time: f32) -> Option<&TexturedModelName> {
+    keyframes.iter()
+        .filter(|keyframe| keyframe.time <= time)
+        .filter_map(|keyframe| match &keyframe.change {
+            KeyframeChange::Mesh(model_name) => Some(model_name),
+            _ => None,
+        })
+        .last()
+}
+
+#[cfg(test)]
+mod tests {
+    use core::time::Duration;
+    use crate::data_store::textured_model_name::TexturedModelName;
+    use crate::game_state::GameState;
+    use crate::game_state::components::motion::{Position, Rotation};
+    use crate::game_state::components::render::MeshInstance;
+    use crate::game_state::components::timeline::{Timeline, TimelineName, TimelineSignal};
+    use crate::game_state::events::GameEvent;
+    use super::system_timeline;
+
+    fn step(state: &mut GameState, seconds: f32) {
+        state.changes.delta_time = Duration::from_secs_f32(seconds);
+        state.events.start_step();
+        system_timeline(state);
+    }
+
+    #[test]
+    fn test_countdown() {
+        let mut state = GameState::new();
+        let hand = state.world.spawn((
+            Position { x: 0.0, y: 0.0, z: 0.0 },
+            Rotation { x: 0.0, y: 0.0, z: 0.0 },
+            MeshInstance::new(TexturedModelName::HandThree),
+            Timeline::new(TimelineName::Countdown, (0.0, 0.0, 0.0)),
+        ));
+
+        // Halfway the hand coming in, it is halfway its way.
+        step(&mut state, 0.75);
+        {
+            let position = state.world.query_one_mut::<&Position>(hand).unwrap();
+            assert_eq!((position.x, position.y, position.z), (0.5, 7.5, 7.5));
+        }
+
+        step(&mut state, 1.0);
+        assert_eq!(state.world.query_one_mut::<&MeshInstance>(hand).unwrap().model_name, TexturedModelName::HandTwo);
+        step(&mut state, 1.0);
+        assert_eq!(state.world.query_one_mut::<&MeshInstance>(hand).unwrap().model_name, TexturedModelName::HandOne);
+        assert!(state.events.this_frame().is_empty());
+
+        // Once the countdown is over, the match starts and the hand is gone.
+        step(&mut state, 1.5);
+        assert_eq!(state.events.this_step(), &[GameEvent::TimelineSignal { entity: hand, signal: TimelineSignal::StartMatch }]);
+        assert!(!state.world.contains(hand));
+    }
+
+    #[test]
+    fn test_chain_and_loop() {
+        let mut state = GameState::new();
+        let pan = state.world.spawn((
+            Position { x: 3.0, y: -10.0, z: 0.0 },
+            Rotation { x: 0.0, y: 0.0, z: 0.0 },
+            Timeline::new(TimelineName::WinRise, (3.0, -10.0, 0.0)),
+        ));
+        step(&mut state, 2.5);
+        {
+            let (timeline, position) = state.world.query_one_mut::<(&Timeline, &Position)>(pan).unwrap();
+            assert_eq!(timeline.name, TimelineName::WinSpin);
+            assert_eq!((position.x, position.y, position.z), (3.0, -5.0, 0.0));
+        }
+
+        let spinner = state.world.spawn((Rotation { x: 0.0, y: 0.0, z: 0.0 }, Timeline::new(TimelineName::Spin, (0.0, 0.0, 0.0))));
+        step(&mut state, 5.0);
+        assert_eq!(state.world.query_one_mut::<&Rotation>(spinner).unwrap().y, 90.0);
+    }
+}
@@ -0,0 +1,97 @@
+use alloc::vec::Vec;
+use hecs::Entity;
+use crate::game_state::GameState;
+use crate::game_state::components::motion::{Position, Rotation, Scale};
+use crate::game_state::components::tween::{Tween, TweenProperty};
+
+/**
+ * Moves every tween along by the time that passed, and puts its entity where the tween says it is.
+ * Finished tweens leave their entity at the end, and are removed.
+ */
+pub fn system_tween(state: &mut GameState) {
+    let delta_time = state.changes.delta_time.as_secs_f32();
+    let mut finished: Vec<Entity> = Vec::new();
+    for (id, (tween, position, rotation, scale)) in state.world.query_mut::<(&mut Tween, Option<&mut Position>, Option<&mut Rotation>, Option<&mut Scale>)>() {
+        tween.advance(delta_time);
+        let (x, y, z) = tween.value();
+        match (tween.property, position, rotation, scale) {
+            (TweenProperty::Position, Some(position), _, _) => {
+                position.x = x;
+                position.y = y;
+                position.z = z;
+            }
+            (TweenProperty::Rotation, _, Some(rotation), _) => {
+                rotation.x = x;
+                rotation.y = y;
+                rotation.z = z;
+            }
+            (TweenProperty::Scale, _, _, Some(scale)) => {
+                scale.x = x;
+                scale.y = y;
+                scale.z = z;
+            }
+            _ => {}
+        }
+        if tween.is_finished() {
+            finished.push(id);
+        }
+    }
+
+    for id in finished {
+        let _ = state.world.remove_one::<Tween>(id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::time::Duration;
+    use strum::IntoEnumIterator;
+    use crate::game_state::GameState;
+    use crate::game_state::components::motion::Position;
+    use crate::game_state::components::tween::{Easing, Tween, TweenProperty};
+    use super::system_tween;
+
+    fn play(tween: Tween, frame_rate: u32, seconds: u32) -> (GameState, hecs::Entity) {
+        let mut state = GameState::new();
+        let entity = state.world.spawn((Position { x: 0.0, y: 0.0, z: 0.0 }, tween));
+        state.changes.delta_time = Duration::from_secs(1) / frame_rate;
+        for _ in 0..frame_rate * seconds {
+            system_tween(&mut state);
+        }
+        (state, entity)
+    }
+
+    #[test]
+    fn test_easings_start_and_end_in_place() {
+        for easing in Easing::iter() {
+            assert!(easing.apply(0.0).abs() < 0.001, "{:?} does not start at 0", easing);
+            assert!((easing.apply(1.0) - 1.0).abs() < 0.001, "{:?} does not end at 1", easing);
+        }
+        assert_eq!(Easing::QuadIn.apply(0.5), 0.25);
+        assert!(Easing::BackOut.apply(0.8) > 1.0);
+    }
+
+    #[test]
+    fn test_tweens_do_not_depend_on_frame_rate() {
+        let halfway = |frame_rate| {
+            let mut tween = Tween::spring(TweenProperty::Position, (0.0, 0.0, 0.0), (10.0, 0.0, 0.0), 1.0, 0.0);
+            for _ in 0..frame_rate / 2 {
+                tween.advance(1.0 / frame_rate as f32);
+            }
+            tween.value().0
+        };
+        assert!((halfway(30) - halfway(60)).abs() < 0.05);
+
+        // Once done, every tween leaves its entity at the end.
+        for tween in [
+            Tween::eased(TweenProperty::Position, (0.0, 0.0, 0.0), (1.0, 2.0, 3.0), 1.5, Easing::ElasticOut),
+            Tween::spring(TweenProperty::Position, (0.0, 0.0, 0.0), (1.0, 2.0, 3.0), 0.5, 0.0),
+            Tween::newtonian(TweenProperty::Position, (0.0, 0.0, 0.0), (1.0, 2.0, 3.0), 1.0, 2.0, 2.0),
+        ] {
+            let (mut state, entity) = play(tween, 30, 5);
+            assert!(state.world.query_one_mut::<&Tween>(entity).is_err());
+            let position = state.world.query_one_mut::<&Position>(entity).unwrap();
+            assert_eq!((position.x, position.y, position.z), (1.0, 2.0, 3.0));
+        }
+    }
+}
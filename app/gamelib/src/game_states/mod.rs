@@ -2,26 +2,60 @@ use crate::game_state::GameState;
 
 mod bouncing_cubes;
 mod moving_platform_test;
+mod pause;
+mod results;
 
 /**
  * Enumerates all game states that exist in the project.
  * Each of them can be turned into its actual state by calling `to_state()` on it.
  */
-#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone)]
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Hash)]
 pub enum GameStateName {
     BouncingCubes,
     MovingPlatform,
+    Pause,
+    Results,
+}
+
+impl GameStateName {
+    /// Whether the state can be continued where it was left (c.f. `Transition::Resume`), so it is remembered when it is left.
+    /// Overlays and menus always start fresh.
+    pub fn can_resume(&self) -> bool {
+        match self {
+            GameStateName::BouncingCubes | GameStateName::MovingPlatform => true,
+            GameStateName::Pause | GameStateName::Results => false,
+        }
+    }
+}
+
+/**
+ * How to go from one state to the next, on the stack of states the game keeps.
+ * A transition is relative to the state that asked for it: any states above that one are removed first.
+ */
+#[derive(Debug, PartialEq, Clone)]
+pub enum Transition {
+    /// Put a fresh state on top of the current one, which is frozen until the new one is popped.
+    Push(GameStateName),
+    /// Remove the current state, and continue the one below it.
+    Pop,
+    /// Replace the current state by a fresh one.
+    Replace(GameStateName),
+    /// Replace the current state by one that continues where it was left the last time.
+    Resume(GameStateName),
 }
 
 /**
- * Returns the state instance of the given particular GameStateName
+ * Returns the state instance of the given particular GameStateName,
+ * drawing its random numbers from the given seed.
  */
 pub struct GameStateFactory {}
 impl GameStateFactory {
-    pub fn to_state(name: GameStateName) -> GameState {
+    pub fn to_state(name: GameStateName, seed: u64) -> GameState {
         match name {
-            GameStateName::BouncingCubes => bouncing_cubes::build(),
-            GameStateName::MovingPlatform => moving_platform_test::build(),
+            GameStateName::BouncingCubes => bouncing_cubes::build(seed),
+            GameStateName::MovingPlatform => moving_platform_test::build(seed),
+            GameStateName::Pause => pause::build(seed),
+            GameStateName::Results => results::build(seed),
         }
     }
 }
\ No newline at end of file
@@ -0,0 +1,18 @@
+use crate::data_store::asset_name::AssetName;
+use crate::game_state::GameState;
+use crate::game_state::scene::load_scene;
+use crate::game_state::systems::system_name::SystemName;
+
+/**
+ * Build the pause state, shown on top of the paused state which stays frozen underneath it.
+ */
+pub fn build(seed: u64) -> GameState {
+    let mut state = GameState::with_seed(seed);
+    state.is_overlay = true;
+    state.updates_below = false;
+    state.add_system(SystemName::ExitAction);
+    state.add_system(SystemName::ResumeAction);
+    state.add_system(SystemName::RenderMeshes);
+    load_scene(&mut state.world, AssetName::PauseScene, &mut state.rng).expect("Invalid pause scene");
+    return state;
+}
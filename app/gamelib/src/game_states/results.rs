@@ -0,0 +1,24 @@
+use crate::data_store::asset_name::AssetName;
+use crate::game_state::GameState;
+use crate::game_state::scene::load_scene;
+use crate::game_state::systems::system_name::SystemName;
+
+/**
+ * Build the results state, which shows how the last match ended (c.f. `GameState::results`)
+ * and lets the players choose what to do next.
+ */
+pub fn build(seed: u64) -> GameState {
+    let mut state = GameState::with_seed(seed);
+    state.add_system(SystemName::ResultsMenu);
+
+    state.add_system(SystemName::Podium);
+    state.add_system(SystemName::Timeline).after(SystemName::Podium);
+    state.add_system(SystemName::Modulate);
+
+    state.add_system(SystemName::CameraUpdate).before(SystemName::RenderMeshes);
+    state.add_system(SystemName::RenderMeshes);
+    state.add_system(SystemName::RenderResults).after(SystemName::RenderMeshes);
+
+    load_scene(&mut state.world, AssetName::ResultsScene, &mut state.rng).expect("Invalid results scene");
+    return state;
+}
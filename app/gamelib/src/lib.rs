@@ -13,3 +13,4 @@ pub mod game;
 pub mod plot;
 pub mod data_store;
 pub mod servers;
+pub mod bytes;
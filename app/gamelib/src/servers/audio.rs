@@ -1,9 +1,10 @@
 use crate::data_store::asset_name::AssetName;
+use strum_macros::{EnumIter, EnumString};
 
 /**
  * Defines how you want the file to be played.
  */
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, EnumIter, EnumString)]
 pub enum PlayMode {
     Infinite,
     OneTime,
@@ -0,0 +1,143 @@
+use micromath::F32Ext;
+use crate::game_state::components::game::Camera;
+use crate::game_state::components::motion::{Position, Scale};
+
+/**
+ * A sphere that holds all vertices of a model, so a mesh can be skipped when its sphere is out of sight.
+ */
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BoundingSphere {
+    pub center: (f32, f32, f32),
+    pub radius: f32,
+}
+
+impl BoundingSphere {
+    /// The sphere around the middle of the bounding box of the given positions, as `x, y, z` triples.
+    pub fn from_positions(positions: &[f32]) -> BoundingSphere {
+        if positions.len() < 3 {
+            return BoundingSphere { center: (0.0, 0.0, 0.0), radius: 0.0 };
+        }
+        let mut min = (f32::MAX, f32::MAX, f32::MAX);
+        let mut max = (f32::MIN, f32::MIN, f32::MIN);
+        for position in positions.chunks_exact(3) {
+            min = (min.0.min(position[0]), min.1.min(position[1]), min.2.min(position[2]));
+            max = (max.0.max(position[0]), max.1.max(position[1]), max.2.max(position[2]));
+        }
+        let center = ((min.0 + max.0) / 2.0, (min.1 + max.1) / 2.0, (min.2 + max.2) / 2.0);
+        let radius = positions.chunks_exact(3)
+            .map(|position| length((position[0] - center.0, position[1] - center.1, position[2] - center.2)))
+            .fold(0.0, f32::max);
+        BoundingSphere { center, radius }
+    }
+
+    /// The sphere of an instance of the model, in the world.
+    /// It is centered on the position of the instance, and grown to hold the model in any rotation.
+    pub fn around(&self, position: &Position, scale: &Scale) -> BoundingSphere {
+        let largest_scale = scale.x.abs().max(scale.y.abs()).max(scale.z.abs());
+        BoundingSphere {
+            center: (position.x, position.y, position.z),
+            radius: (length(self.center) + self.radius) * largest_scale,
+        }
+    }
+}
+
+/**
+ * What the camera can see: everything in between the near and far distance, within its field of view.
+ */
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Frustum {
+    origin: (f32, f32, f32),
+    forward: (f32, f32, f32),
+    right: (f32, f32, f32),
+    up: (f32, f32, f32),
+    near: f32,
+    far: f32,
+    /// The tangent of half the vertical field of view.
+    tan_y: f32,
+    /// The tangent of half the horizontal field of view.
+    tan_x: f32,
+}
+
+impl Frustum {
+    /// The frustum of a camera at the given position, with the vertical field of view in degrees
+    /// and the aspect ratio (width over height) of the screen.
+    pub fn new(position: &Position, camera: &Camera, fov: f32, aspect: f32, near: f32, far: f32) -> Frustum {
+        let origin = (position.x, position.y, position.z);
+        let forward = normalize((camera.lookat_x - position.x, camera.lookat_y - position.y, camera.lookat_z - position.z));
+        let right = normalize(cross(forward, (camera.up_x, camera.up_y, camera.up_z)));
+        let up = cross(right, forward);
+        let tan_y = (fov.to_radians() / 2.0).tan();
+        Frustum { origin, forward, right, up, near, far, tan_y, tan_x: tan_y * aspect }
+    }
+
+    /// Whether any part of the sphere can be seen.
+    pub fn intersects(&self, sphere: &BoundingSphere) -> bool {
+        let offset = (sphere.center.0 - self.origin.0, sphere.center.1 - self.origin.1, sphere.center.2 - self.origin.2);
+        let depth = dot(offset, self.forward);
+        if depth < self.near - sphere.radius || depth > self.far + sphere.radius {
+            return false;
+        }
+        // The distance from the sphere to a side plane is its distance past the edge of the view, times the cosine of half the view angle.
+        let outside = |distance: f32, tan: f32| (distance.abs() - depth * tan) / (1.0 + tan * tan).sqrt() > sphere.radius;
+        !outside(dot(offset, self.right), self.tan_x) && !outside(dot(offset, self.up), self.tan_y)
+    }
+}
+
+fn dot(a: (f32, f32, f32), b: (f32, f32, f32)) -> f32 {
+    a.0 * b.0 + a.1 * b.1 + a.2 * b.2
+}
+
+fn cross(a: (f32, f32, f32), b: (f32, f32, f32)) -> (f32, f32, f32) {
+    (a.1 * b.2 - a.2 * b.1, a.2 * b.0 - a.0 * b.2, a.0 * b.1 - a.1 * b.0)
+}
+
+fn length(a: (f32, f32, f32)) -> f32 {
+    dot(a, a).sqrt()
+}
+
+fn normalize(a: (f32, f32, f32)) -> (f32, f32, f32) {
+    let length = length(a);
+    if length == 0.0 {
+        return a;
+    }
+    (a.0 / length, a.1 / length, a.2 / length)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::game_state::components::game::Camera;
+    use crate::game_state::components::motion::{Position, Scale};
+    use super::{BoundingSphere, Frustum};
+
+    #[test]
+    fn test_bounding_sphere() {
+        let cube = [-1.0, -1.0, -1.0, 1.0, 1.0, 1.0, 1.0, -1.0, 1.0];
+        let sphere = BoundingSphere::from_positions(&cube);
+        assert_eq!(sphere.center, (0.0, 0.0, 0.0));
+        assert!((sphere.radius - 3.0f32.sqrt()).abs() < 0.001);
+
+        let moved = sphere.around(&Position { x: 5.0, y: 0.0, z: 0.0 }, &Scale { x: 1.0, y: 2.0, z: 1.0 });
+        assert_eq!(moved.center, (5.0, 0.0, 0.0));
+        assert!((moved.radius - 2.0 * 3.0f32.sqrt()).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_frustum() {
+        let camera = Camera {
+            r: 0, g: 0, b: 0,
+            up_x: 0.0, up_y: 1.0, up_z: 0.0,
+            lookat_x: 0.0, lookat_y: 0.0, lookat_z: 0.0,
+        };
+        let frustum = Frustum::new(&Position { x: 0.0, y: 0.0, z: 20.0 }, &camera, 45.0, 4.0 / 3.0, 0.1, 100.0);
+        let sphere = |x: f32, y: f32, z: f32, radius: f32| BoundingSphere { center: (x, y, z), radius };
+        assert!(frustum.intersects(&sphere(0.0, 0.0, 0.0, 1.0)));
+        // Behind the camera, and too far away.
+        assert!(!frustum.intersects(&sphere(0.0, 0.0, 25.0, 1.0)));
+        assert!(!frustum.intersects(&sphere(0.0, 0.0, -90.0, 1.0)));
+        // Wider than it is high: at 20 away, the view is about 8.3 high and 11 wide from the middle.
+        assert!(frustum.intersects(&sphere(10.0, 0.0, 0.0, 0.5)));
+        assert!(!frustum.intersects(&sphere(0.0, 10.0, 0.0, 0.5)));
+        // Only just poking into the view.
+        assert!(frustum.intersects(&sphere(0.0, 10.0, 0.0, 2.0)));
+    }
+}
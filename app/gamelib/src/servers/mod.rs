@@ -1,8 +1,14 @@
 pub mod audio;
 pub mod renderer;
+pub mod culling;
+pub mod physics;
+pub mod simple_physics;
+#[cfg(not(feature = "wii"))]
+pub mod recording;
 
 use crate::servers::audio::AudioServer;
 use crate::servers::renderer::RenderServer;
+use crate::servers::physics::PhysicsServer;
 
 #[cfg(feature = "wii")]
 use ogc_rs::prelude::Box;
@@ -11,14 +17,20 @@ use std::boxed::Box;
 
 pub struct ServerProvider {
     pub audio_server: Box<dyn AudioServer>,
-    pub render_server: Box<dyn RenderServer>
+    pub render_server: Box<dyn RenderServer>,
+    pub physics_server: Box<dyn PhysicsServer>,
 }
 
 impl ServerProvider {
-    pub fn new(audio_server: impl AudioServer + 'static, render_server: impl RenderServer + 'static) -> Self {
+    pub fn new(
+        audio_server: impl AudioServer + 'static,
+        render_server: impl RenderServer + 'static,
+        physics_server: impl PhysicsServer + 'static,
+    ) -> Self {
         Self {
             audio_server: Box::new(audio_server),
-            render_server: Box::new(render_server)
+            render_server: Box::new(render_server),
+            physics_server: Box::new(physics_server),
         }
     }
-}
\ No newline at end of file
+}
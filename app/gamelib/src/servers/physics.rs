@@ -0,0 +1,46 @@
+use crate::game_state::components::{motion::Position, motion::Rotation, physics::SphereCollider};
+#[cfg(feature = "wii")]
+use ogc_rs::prelude::Vec;
+#[cfg(not(feature = "wii"))]
+use std::vec::Vec;
+use crate::game_state::changes::controls::Direction;
+use crate::game_state::components::game::PotatoKind;
+
+/// Height below which a potato has dropped off the plate, and has landed in a fry pan or the oil.
+pub const RESPAWN_HEIGHT: f32 = -20.0;
+/// A potato that lands at most this far from the center of a fry pan lands in it.
+pub const FRY_PAN_RADIUS: f32 = 10.0;
+
+/**
+ * Simple trait for implementing the physics simulation, separate from rendering.
+ */
+pub trait PhysicsServer {
+    /// Adds a body for every collider that has not been registered yet, shaped after the kind of potato it belongs to.
+    fn register_collider(&mut self, colliders: &mut Vec::<(&mut SphereCollider, Option<&PotatoKind>)>);
+    fn world_step(&mut self);
+    fn physics_to_position(&mut self, temp: &mut Vec<(&mut SphereCollider, &mut Position, &mut Rotation)>);
+    fn apply_movement(&mut self, obj: &SphereCollider, dir: Direction);
+    fn reset_world(&mut self);
+    /// Puts the body of the collider at the given position, at rest.
+    fn move_body_to(&mut self, obj: &SphereCollider, position: &Position);
+    /// Takes the body of the collider out of the simulation, for when its entity is despawned.
+    /// The indices of the other bodies stay the same.
+    fn remove_body(&mut self, obj: &SphereCollider);
+    /// Number of bodies that are being simulated.
+    fn body_count(&self) -> usize;
+}
+
+/**
+ * The velocity change a motion in the given direction applies to a body.
+ * Shared by all physics backends, so the controls feel the same everywhere.
+ */
+pub fn movement_for_direction(dir: Direction) -> (f32, f32, f32) {
+    let move_magnitude = 0.1;
+    let move_help_jump_magnitude = 0.1;
+    match dir {
+        Direction::Xp => (-move_magnitude, move_help_jump_magnitude, 0.0),
+        Direction::Xn => (move_magnitude, move_help_jump_magnitude, 0.0),
+        Direction::Yp | Direction::Zp => (0.0, move_help_jump_magnitude, move_magnitude),
+        Direction::Yn | Direction::Zn => (0.0, move_help_jump_magnitude, -move_magnitude),
+    }
+}
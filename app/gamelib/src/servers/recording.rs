@@ -0,0 +1,276 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::vec::Vec;
+
+use crate::data_store::asset_name::AssetName;
+use crate::data_store::textured_model_name::TexturedModelName;
+use crate::game_state::components::game::Camera;
+use crate::game_state::components::light::Light;
+use crate::game_state::components::motion::{Position, Rotation, Scale};
+use crate::game_state::components::physics::SphereCollider;
+use crate::game_state::components::render::{Colour, MeshInstance};
+use crate::servers::audio::{AudioServer, PlayMode};
+use crate::servers::renderer::{Billboard, RenderServer, RenderStats};
+use crate::servers::simple_physics::SimplePhysicsServer;
+use crate::servers::ServerProvider;
+
+/**
+ * A mesh as it was handed to the render server.
+ */
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecordedMesh {
+    pub model_name: TexturedModelName,
+    pub position: (f32, f32, f32),
+    pub rotation: (f32, f32, f32),
+    pub scale: (f32, f32, f32),
+    pub tint: Colour,
+}
+
+/**
+ * A camera update as it was handed to the render server.
+ */
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecordedCamera {
+    pub position: (f32, f32, f32),
+    pub up: (f32, f32, f32),
+    pub look_at: (f32, f32, f32),
+}
+
+/**
+ * A light as it was handed to the render server.
+ */
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecordedLight {
+    pub light: Light,
+    pub position: Option<(f32, f32, f32)>,
+}
+
+/**
+ * Text as it was handed to the render server.
+ */
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecordedText {
+    pub text: String,
+    pub position: (f32, f32),
+    pub size: f32,
+    pub colour: Colour,
+}
+
+/**
+ * A filled rectangle as it was handed to the render server.
+ */
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecordedQuad {
+    pub position: (f32, f32),
+    pub size: (f32, f32),
+    pub colour: Colour,
+}
+
+/**
+ * A single call made to the audio server.
+ */
+#[derive(Debug, Clone, PartialEq)]
+pub enum RecordedAudio {
+    Play(AssetName, PlayMode),
+    SetVolume(u32),
+    Stop,
+}
+
+/**
+ * Everything the servers were asked to do during one rendered frame.
+ */
+#[derive(Debug, Clone, Default)]
+pub struct RecordedFrame {
+    pub meshes: Vec<RecordedMesh>,
+    pub debug_meshes: Vec<RecordedMesh>,
+    pub cameras: Vec<RecordedCamera>,
+    /// The lights of the last call to `update_lights` this frame.
+    pub lights: Vec<RecordedLight>,
+    pub texts: Vec<RecordedText>,
+    pub quads: Vec<RecordedQuad>,
+    pub billboards: Vec<Billboard>,
+    pub audio: Vec<RecordedAudio>,
+}
+
+impl RecordedFrame {
+    /// Number of meshes of the given model that were rendered this frame.
+    pub fn count_meshes(&self, model_name: &TexturedModelName) -> usize {
+        self.meshes.iter().filter(|mesh| &mesh.model_name == model_name).count()
+    }
+
+    /// Whether the given text was drawn this frame.
+    pub fn has_text(&self, text: &str) -> bool {
+        self.texts.iter().any(|recorded| recorded.text == text)
+    }
+}
+
+/**
+ * Shared log of all server calls, split per rendered frame.
+ *
+ * A frame is closed whenever `render_frame` is called on the render server,
+ * everything recorded after that ends up in the next frame.
+ */
+#[derive(Debug, Default)]
+pub struct Recording {
+    frames: Vec<RecordedFrame>,
+    current: RecordedFrame,
+}
+
+impl Recording {
+    pub fn shared() -> Rc<RefCell<Recording>> {
+        Rc::new(RefCell::new(Recording::default()))
+    }
+
+    /// All frames that have been finished so far.
+    pub fn frames(&self) -> &[RecordedFrame] {
+        &self.frames
+    }
+
+    /// The frame that is currently being recorded.
+    pub fn current_frame(&self) -> &RecordedFrame {
+        &self.current
+    }
+
+    /// All audio calls over all finished frames, in order.
+    pub fn audio_calls(&self) -> impl Iterator<Item = &RecordedAudio> {
+        self.frames.iter().flat_map(|frame| frame.audio.iter())
+    }
+
+    fn finish_frame(&mut self) {
+        let frame = core::mem::take(&mut self.current);
+        self.frames.push(frame);
+    }
+}
+
+/**
+ * Creates a server provider whose render and audio servers record into a single shared `Recording`.
+ * Physics is simulated by the `SimplePhysicsServer`.
+ * The returned recording can be inspected while (and after) the game runs.
+ */
+pub fn recording_server_provider() -> (ServerProvider, Rc<RefCell<Recording>>) {
+    let recording = Recording::shared();
+    let server_provider = ServerProvider::new(
+        RecordingAudioServer::new(recording.clone()),
+        RecordingRenderServer::new(recording.clone()),
+        SimplePhysicsServer::new(),
+    );
+    (server_provider, recording)
+}
+
+/**
+ * Render server that does not draw anything, but remembers every call made to it.
+ */
+pub struct RecordingRenderServer {
+    recording: Rc<RefCell<Recording>>,
+}
+
+impl RecordingRenderServer {
+    pub fn new(recording: Rc<RefCell<Recording>>) -> Self {
+        Self { recording }
+    }
+
+    fn record_mesh(model_name: &TexturedModelName, position: &Position, rotation: &Rotation, scale: &Scale, tint: &Colour) -> RecordedMesh {
+        RecordedMesh {
+            model_name: model_name.clone(),
+            position: (position.x, position.y, position.z),
+            rotation: (rotation.x, rotation.y, rotation.z),
+            scale: (scale.x, scale.y, scale.z),
+            tint: *tint,
+        }
+    }
+}
+
+impl RenderServer for RecordingRenderServer {
+    /// Records every mesh, none are culled.
+    fn render_meshes(&mut self, meshes: Vec<(&MeshInstance, &Position, &Rotation, &Scale)>) -> RenderStats {
+        let mut recording = self.recording.borrow_mut();
+        let drawn = meshes.len();
+        for (mesh_instance, position, rotation, scale) in meshes {
+            let mesh = Self::record_mesh(&mesh_instance.model_name, position, rotation, scale, &mesh_instance.tint);
+            recording.current.meshes.push(mesh);
+        }
+        RenderStats { drawn, culled: 0 }
+    }
+
+    fn update_lights(&mut self, lights: Vec<(&Light, Option<&Position>)>) {
+        self.recording.borrow_mut().current.lights = lights.into_iter()
+            .map(|(light, position)| RecordedLight {
+                light: *light,
+                position: position.map(|position| (position.x, position.y, position.z)),
+            })
+            .collect();
+    }
+
+    fn render_debug(&mut self, meshes: Vec<(&Position, &SphereCollider, &Rotation)>) {
+        let mut recording = self.recording.borrow_mut();
+        for (position, _collider, rotation) in meshes {
+            let mesh = Self::record_mesh(&TexturedModelName::Cube, position, rotation, &Scale::ONE, &Colour::WHITE);
+            recording.current.debug_meshes.push(mesh);
+        }
+    }
+
+    fn render_frame(&mut self) {
+        self.recording.borrow_mut().finish_frame();
+    }
+
+    fn update_camera(&mut self, pos: &Position, cam: &Camera) {
+        self.recording.borrow_mut().current.cameras.push(RecordedCamera {
+            position: (pos.x, pos.y, pos.z),
+            up: (cam.up_x, cam.up_y, cam.up_z),
+            look_at: (cam.lookat_x, cam.lookat_y, cam.lookat_z),
+        });
+    }
+
+    fn render_text(&mut self, text: &str, x: f32, y: f32, size: f32, colour: &Colour) {
+        self.recording.borrow_mut().current.texts.push(RecordedText {
+            text: text.to_string(),
+            position: (x, y),
+            size,
+            colour: *colour,
+        });
+    }
+
+    /// Every character is as wide as it is high, like those of the font on the Wii.
+    fn text_width(&self, text: &str, size: f32) -> f32 {
+        text.chars().count() as f32 * size
+    }
+
+    fn render_quad(&mut self, x: f32, y: f32, width: f32, height: f32, colour: &Colour) {
+        self.recording.borrow_mut().current.quads.push(RecordedQuad {
+            position: (x, y),
+            size: (width, height),
+            colour: *colour,
+        });
+    }
+
+    fn render_billboards(&mut self, billboards: Vec<Billboard>) {
+        self.recording.borrow_mut().current.billboards.extend(billboards);
+    }
+}
+
+/**
+ * Audio server that stays silent, but remembers every call made to it.
+ */
+pub struct RecordingAudioServer {
+    recording: Rc<RefCell<Recording>>,
+}
+
+impl RecordingAudioServer {
+    pub fn new(recording: Rc<RefCell<Recording>>) -> Self {
+        Self { recording }
+    }
+}
+
+impl AudioServer for RecordingAudioServer {
+    fn play(&self, audio: &AssetName, play_mode: PlayMode) {
+        self.recording.borrow_mut().current.audio.push(RecordedAudio::Play(audio.clone(), play_mode));
+    }
+
+    fn set_volume(&self, volume: u32) {
+        self.recording.borrow_mut().current.audio.push(RecordedAudio::SetVolume(volume));
+    }
+
+    fn stop(&self) {
+        self.recording.borrow_mut().current.audio.push(RecordedAudio::Stop);
+    }
+}
@@ -1,28 +1,58 @@
 use crate::game_state::components::game::Camera;
-use crate::game_state::components::{render::MeshInstance, motion::Position, motion::Rotation, physics::SphereCollider};
+use crate::game_state::components::light::Light;
+use crate::game_state::components::{render::Colour, render::MeshInstance, motion::Position, motion::Rotation, motion::Scale, physics::SphereCollider};
 #[cfg(feature = "wii")]
 use ogc_rs::prelude::Vec;
-use ogc_rs::println;
 #[cfg(not(feature = "wii"))]
 use std::vec::Vec;
-use crate::game_state::changes::controls::Direction;
-use crate::game_state::components::game::FryAssignment;
-use crate::game_state::GameState;
-use crate::game_states::GameStateName;
 
 /**
  * Simple trait for implementing the wii specific renderer.
  */
 pub trait RenderServer {
-    fn render_meshes(&mut self, meshes: Vec::<(&MeshInstance, &Position, &Rotation)>);
+    /// Draws every mesh at its position, rotation and scale, tinted by its `MeshInstance::tint`.
+    /// Meshes the camera cannot see may be skipped, which is counted in the returned stats.
+    /// See-through meshes (with a tint alpha below 255) are drawn after all others, from back to front.
+    fn render_meshes(&mut self, meshes: Vec::<(&MeshInstance, &Position, &Rotation, &Scale)>) -> RenderStats;
+    /// Sets the lights the meshes rendered after it are lit by, with the position of each light (if it has one).
+    /// Without any lights, meshes are rendered unlit.
+    fn update_lights(&mut self, lights: Vec::<(&Light, Option<&Position>)>);
     fn render_debug(&mut self, meshes: Vec::<(&Position, &SphereCollider, &Rotation)>);
     fn render_frame(&mut self);
     fn update_camera(&mut self, pos: &Position, cam: &Camera);
-    fn register_collider(&mut self, colliders: &mut Vec::<&mut SphereCollider>);
-    fn world_step(&mut self);
-    fn physics_to_position(&mut self, temp: &mut Vec<(&mut SphereCollider, &mut Position, &mut Rotation)>);
-    fn teleport_potato(&mut self, temp: &mut Vec<(&mut SphereCollider, &mut Position, &mut Rotation)>);
-    fn apply_movement(&mut self, obj: &SphereCollider, dir: Direction);
-    fn reset_world(&mut self);
-    fn fry_pan_score_increase(&mut self, position: &mut Position, fry_assignment: &mut FryAssignment, potatoes: &Vec<usize>);
+    /// Draws text on top of the 3D scene, with its top left corner at the given screen position in pixels.
+    /// `size` is the height of a character in pixels.
+    fn render_text(&mut self, text: &str, x: f32, y: f32, size: f32, colour: &Colour);
+    /// How wide the text is in pixels when it is drawn with `render_text` at the given size.
+    fn text_width(&self, text: &str, size: f32) -> f32;
+    /// Draws a filled rectangle on top of the 3D scene, with its top left corner at the given screen position in pixels.
+    fn render_quad(&mut self, x: f32, y: f32, width: f32, height: f32, colour: &Colour);
+    /// Draws every billboard as a square that faces the camera, on top of the meshes but behind anything in front of it.
+    fn render_billboards(&mut self, billboards: Vec::<Billboard>);
 }
+
+/**
+ * A flat square in the 3D scene that always faces the camera, like a particle.
+ */
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Billboard {
+    /// The middle of the square, in the world.
+    pub position: (f32, f32, f32),
+    /// The width (and height) of the square.
+    pub size: f32,
+    pub colour: Colour,
+}
+
+/**
+ * How many meshes were drawn by a call to `render_meshes`, and how many were skipped as the camera could not see them.
+ */
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct RenderStats {
+    pub drawn: usize,
+    pub culled: usize,
+}
+
+/// Width of the screen in pixels, as used by `render_text` and `render_quad`.
+pub const SCREEN_WIDTH: f32 = 640.0;
+/// Height of the screen in pixels, as used by `render_text` and `render_quad`.
+pub const SCREEN_HEIGHT: f32 = 480.0;
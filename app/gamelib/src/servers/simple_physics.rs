@@ -0,0 +1,153 @@
+use alloc::vec::Vec;
+use crate::game_state::changes::controls::Direction;
+use crate::game_state::components::game::PotatoKind;
+use crate::game_state::components::motion::{Position, Rotation};
+use crate::game_state::components::physics::SphereCollider;
+use crate::servers::physics::{movement_for_direction, PhysicsServer};
+
+const GRAVITY: f32 = 1.0 / 100.0;
+const PLATE_HEIGHT: f32 = 0.0;
+const PLATE_RADIUS: f32 = 11.0;
+const STEP_SCALE_VELOCITY: usize = 1;
+
+struct SimpleBody {
+    position: (f32, f32, f32),
+    velocity: (f32, f32, f32),
+    /// Removed bodies keep their place, so the indices of the other bodies stay valid.
+    removed: bool,
+}
+
+/**
+ * Rendering-less physics backend in plain Rust, so the simulation can also run on the host.
+ *
+ * Bodies are point masses following the same plate and gravity rules
+ * as the tiny_physics world on the Wii. Collisions between bodies are not resolved.
+ */
+pub struct SimplePhysicsServer {
+    bodies: Vec<SimpleBody>,
+    sim_step: usize,
+}
+
+impl SimplePhysicsServer {
+    pub fn new() -> Self {
+        Self {
+            bodies: Vec::new(),
+            sim_step: 0,
+        }
+    }
+
+    fn is_on_plate(position: &(f32, f32, f32)) -> bool {
+        let distance_from_center = position.0 * position.0 + position.2 * position.2;
+        distance_from_center < PLATE_RADIUS * PLATE_RADIUS
+            && position.1 < PLATE_HEIGHT
+            && position.1 > PLATE_HEIGHT - 2.0
+    }
+}
+
+impl Default for SimplePhysicsServer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PhysicsServer for SimplePhysicsServer {
+    fn register_collider(&mut self, colliders: &mut Vec<(&mut SphereCollider, Option<&PotatoKind>)>) {
+        // Bodies are point masses, so the kind of potato does not matter here.
+        for (collider, _kind) in colliders.iter_mut() {
+            if !collider.has_been_registered {
+                let offset = self.bodies.len() as f32 * 0.5;
+                self.bodies.push(SimpleBody {
+                    position: (offset, 20.0 + offset, offset),
+                    velocity: (0.0, 0.0, 0.0),
+                    removed: false,
+                });
+                collider.body_index = self.bodies.len() - 1;
+                collider.has_been_registered = true;
+            }
+        }
+    }
+
+    fn world_step(&mut self) {
+        for body in self.bodies.iter_mut().filter(|body| !body.removed) {
+            if Self::is_on_plate(&body.position) {
+                body.position.1 = PLATE_HEIGHT;
+                body.velocity.1 = 0.0;
+                if self.sim_step >= STEP_SCALE_VELOCITY {
+                    body.velocity.0 *= 0.99;
+                    body.velocity.2 *= 0.99;
+                }
+            } else {
+                body.velocity.1 -= GRAVITY;
+            }
+            body.position.0 += body.velocity.0;
+            body.position.1 += body.velocity.1;
+            body.position.2 += body.velocity.2;
+        }
+
+        if self.sim_step >= STEP_SCALE_VELOCITY {
+            self.sim_step = 0;
+        } else {
+            self.sim_step += 1;
+        }
+    }
+
+    fn physics_to_position(&mut self, objs: &mut Vec<(&mut SphereCollider, &mut Position, &mut Rotation)>) {
+        for (col, pos, _rot) in objs.iter_mut() {
+            let body = &self.bodies[col.body_index];
+            pos.x = body.position.0;
+            pos.y = body.position.1;
+            pos.z = body.position.2;
+        }
+    }
+
+    fn apply_movement(&mut self, obj: &SphereCollider, dir: Direction) {
+        let body = &mut self.bodies[obj.body_index];
+        let movement = movement_for_direction(dir);
+        body.velocity.0 += movement.0;
+        body.velocity.1 += movement.1;
+        body.velocity.2 += movement.2;
+    }
+
+    fn reset_world(&mut self) {
+        self.bodies = Vec::new();
+        self.sim_step = 0;
+    }
+
+    fn move_body_to(&mut self, obj: &SphereCollider, position: &Position) {
+        let body = &mut self.bodies[obj.body_index];
+        body.position = (position.x, position.y, position.z);
+        body.velocity = (0.0, 0.0, 0.0);
+    }
+
+    fn remove_body(&mut self, obj: &SphereCollider) {
+        if let Some(body) = self.bodies.get_mut(obj.body_index) {
+            body.removed = true;
+        }
+    }
+
+    fn body_count(&self) -> usize {
+        self.bodies.iter().filter(|body| !body.removed).count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+    use crate::game_state::components::physics::SphereCollider;
+    use crate::servers::physics::PhysicsServer;
+    use super::SimplePhysicsServer;
+
+    #[test]
+    fn test_body_lands_on_plate() {
+        let mut physics = SimplePhysicsServer::new();
+        let mut collider = SphereCollider { radius: 1.0, gravity: true, body_index: 0, has_been_registered: false };
+        physics.register_collider(&mut vec![(&mut collider, None)]);
+        assert!(collider.has_been_registered);
+
+        for _ in 0..200 {
+            physics.world_step();
+        }
+        let body = &physics.bodies[collider.body_index];
+        assert!(body.position.1.abs() < 0.1);
+    }
+}
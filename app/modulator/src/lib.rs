@@ -1,4 +1,5 @@
-#![no_std]
+// In Wii mode, enable `no_std` and use the ogc_rs replacements of common types.
+#![cfg_attr(feature = "wii", no_std)]
 extern crate alloc;
 
 pub mod sources;
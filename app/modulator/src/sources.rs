@@ -1,8 +1,12 @@
 use alloc::boxed::Box;
 use core::any::Any;
 use micromath::F32Ext;
+#[cfg(feature = "wii")]
 use ogc_rs::prelude::*;
+#[cfg(feature = "wii")]
 use ogc_rs::mutex::Mutex;
+#[cfg(not(feature = "wii"))]
+use std::sync::Mutex;
 use rand::{Rng, SeedableRng};
 use rand::rngs::SmallRng;
 use lazy_static::lazy_static;
@@ -10,12 +14,28 @@ use lazy_static::lazy_static;
 use crate::{Modulator, ModulatorEnv};
 
 lazy_static! {
+    /// Seeds the generators of the modulators that are not given a seed of their own.
     static ref GLOBAL_RNG: Mutex<SmallRng> = {
         let rng = SmallRng::seed_from_u64(10u64);
-        Mutex::new(rng).unwrap()
+        new_mutex(rng)
     };
 }
 
+#[cfg(feature = "wii")]
+fn new_mutex<T>(value: T) -> Mutex<T> {
+    Mutex::new(value).unwrap()
+}
+
+#[cfg(not(feature = "wii"))]
+fn new_mutex<T>(value: T) -> Mutex<T> {
+    Mutex::new(value)
+}
+
+/// A generator for a single modulator, seeded from the global one
+fn new_rng() -> SmallRng {
+    SmallRng::seed_from_u64(GLOBAL_RNG.lock().unwrap().gen())
+}
+
 /// Simple modulator using a value closure/`Fn`, with frequency and amplitude. The
 /// closure receives self, elapsed time (in seconds) and returns a new value.
 pub struct Wave {
@@ -236,6 +256,9 @@ pub struct ScalarGoalFollower {
 
     /// Enabling toggle
     pub enabled: bool,
+
+    // Generator the regions, goals and pauses are picked with
+    rng: SmallRng,
 }
 
 impl ScalarGoalFollower {
@@ -257,15 +280,23 @@ impl ScalarGoalFollower {
             time: 0,
 
             enabled: true,
+
+            rng: new_rng(),
         }
     }
 
+    /// Pick regions, goals and pauses from a generator with the given seed, so they can be repeated
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.rng = SmallRng::seed_from_u64(seed);
+        self
+    }
+
     /// Select and begin following a new goal
     fn set_new_goal(&mut self) {
         let n = self.regions.len(); // current number of regions, if 0 we do nothing here
         if n > 0 {
             self.current_region = if self.random_region {
-                GLOBAL_RNG.lock().unwrap().gen_range(0..n)
+                self.rng.gen_range(0..n)
             } else if self.current_region + 1 < n {
                 self.current_region + 1
             } else {
@@ -274,7 +305,7 @@ impl ScalarGoalFollower {
 
             let region = &self.regions[self.current_region]; // region we are going to
             let goal = if region[1] > region[0] {
-                GLOBAL_RNG.lock().unwrap().gen_range(region[0]..region[1])
+                self.rng.gen_range(region[0]..region[1])
             } else {
                 region[0]
             };
@@ -350,7 +381,7 @@ impl Modulator<f32> for ScalarGoalFollower {
             }
 
             self.paused_left = if self.pause_range[1] > self.pause_range[0] {
-                GLOBAL_RNG.lock().unwrap().gen_range(self.pause_range[0]..self.pause_range[1])
+                self.rng.gen_range(self.pause_range[0]..self.pause_range[1])
             } else {
                 self.pause_range[0]
             };
@@ -410,6 +441,8 @@ pub struct Newtonian {
     f: f32,
     // Phase times: t[0] accel, t[1] sustain, t[2] decel
     t: [f32; 3],
+    // Generator the speed, accel and decel are picked with
+    rng: SmallRng,
 }
 
 impl Newtonian {
@@ -437,9 +470,16 @@ impl Newtonian {
             d: 0.0,
             f: initial,
             t: [0.0; 3],
+            rng: new_rng(),
         }
     }
 
+    /// Pick speeds, accelerations and decelerations from a generator with the given seed, so they can be repeated
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.rng = SmallRng::seed_from_u64(seed);
+        self
+    }
+
     /// Manual reset of the current value - also ends all motion
     pub fn reset(&mut self, value: f32) {
         self.value = value;
@@ -457,9 +497,9 @@ impl Newtonian {
         self.time = 0; // for this modulator time is elapsed us in goal
         self.goal = goal;
 
-        self.s = Newtonian::gen_value(&self.speed_limit);
-        self.a = Newtonian::gen_value(&self.acceleration);
-        self.d = Newtonian::gen_value(&self.deceleration);
+        self.s = Newtonian::gen_value(&mut self.rng, &self.speed_limit);
+        self.a = Newtonian::gen_value(&mut self.rng, &self.acceleration);
+        self.d = Newtonian::gen_value(&mut self.rng, &self.deceleration);
         self.f = self.value;
 
         self.calculate_events();
@@ -522,9 +562,9 @@ impl Newtonian {
     }
 
     /// Get a value in the given range (need the test since gen_range panics on a null range)
-    fn gen_value(r: &[f32]) -> f32 {
+    fn gen_value(rng: &mut SmallRng, r: &[f32]) -> f32 {
         if r[1] > r[0] {
-            GLOBAL_RNG.lock().unwrap().gen_range(r[0]..r[1])
+            rng.gen_range(r[0]..r[1])
         } else {
             r[0]
         }
@@ -623,6 +663,9 @@ pub struct ShiftRegister {
 
     /// Enabling toggle
     pub enabled: bool,
+
+    // Generator the bucket values and their changes are picked with
+    rng: SmallRng,
 }
 
 /// Available choices of interpolation for shift registers
@@ -644,7 +687,8 @@ impl ShiftRegister {
         period: f32,
         interp: ShiftRegisterInterp,
     ) -> Self {
-        let b = Self::new_buckets(buckets, &value_range);
+        let mut rng = new_rng();
+        let b = Self::new_buckets(&mut rng, buckets, &value_range);
         let v = if buckets > 0 { b[0] } else { 0.0 };
 
         ShiftRegister {
@@ -662,14 +706,25 @@ impl ShiftRegister {
             value: v,
 
             enabled: true,
+
+            rng,
         }
     }
 
+    /// Pick the bucket values and their changes from a generator with the given seed, so they
+    /// can be repeated - the buckets are filled anew
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.rng = SmallRng::seed_from_u64(seed);
+        self.buckets = Self::new_buckets(&mut self.rng, self.buckets.len(), &self.value_range);
+        self.value = if self.buckets.is_empty() { 0.0 } else { self.buckets[0] };
+        self
+    }
+
     /// Make a new bucket vector of `buckets` capacity, fill it with values from `range`
-    fn new_buckets(buckets: usize, range: &[f32; 2]) -> Vec<f32> {
+    fn new_buckets(rng: &mut SmallRng, buckets: usize, range: &[f32; 2]) -> Vec<f32> {
         let mut b = Vec::with_capacity(buckets);
         for _ in 0..buckets {
-            b.push(GLOBAL_RNG.lock().unwrap().gen_range(range[0]..range[1]));
+            b.push(rng.gen_range(range[0]..range[1]));
         }
         b // moves it out
     }
@@ -759,8 +814,8 @@ impl Modulator<f32> for ShiftRegister {
                 odds = odds + (1.0 - odds) * t;
             }
 
-            if GLOBAL_RNG.lock().unwrap().gen_range(0.0..1.0) < odds {
-                self.buckets[bh] = GLOBAL_RNG.lock().unwrap().gen_range(self.value_range[0]..self.value_range[1]);
+            if self.rng.gen_range(0.0..1.0) < odds {
+                self.buckets[bh] = self.rng.gen_range(self.value_range[0]..self.value_range[1]);
                 self.ages[bh] = 0;
             } else {
                 self.ages[bh] += 1; // another period without changing value
@@ -117,6 +117,11 @@ impl Body {
         unsafe { TPE_bodyIsActive(&self.0) != 0 }
     }
 
+    /// True if the body no longer takes part in the simulation, c.f. `WorldWrapper::remove_body`.
+    pub fn is_disabled(&self) -> bool {
+        self.0.flags & TPE_BODY_FLAG_DISABLED as u8 != 0
+    }
+
     /** Adds angular velocity to a soft body. The rotation vector specifies the axis
         of rotation by its direction and angular velocity by its magnitude (magnitude
         of 1.0 will add linear velocity of 2 PI
@@ -337,7 +342,7 @@ impl WorldWrapper {
     }
 
     /// Add a body to the world.
-    /// Removing a body (or altering its joints/connections) is intentionally not allowed
+    /// Altering the joints/connections of a body is intentionally not allowed
     ///
     /// Returns the index that the body was added as
     #[must_use]
@@ -356,6 +361,19 @@ impl WorldWrapper {
         self.world.0.bodyCount = self.bodies_vec.len() as u16;
     }
 
+    /// Takes a body out of the simulation.
+    /// It is disabled rather than dropped, so the indices of the other bodies stay valid.
+    pub fn remove_body(&mut self, body_index: usize) {
+        if let Some(body) = self.bodies_vec.get_mut(body_index) {
+            body.0.flags |= TPE_BODY_FLAG_DISABLED as u8;
+        }
+    }
+
+    /// Number of bodies that take part in the simulation.
+    pub fn body_count(&self) -> usize {
+        self.bodies_vec.iter().filter(|body| !body.is_disabled()).count()
+    }
+
     /// Simulate one step of the world physics
     pub fn step(&mut self) {
         self.world.step();
@@ -366,8 +384,9 @@ impl WorldWrapper {
         &mut self.bodies_vec[body_index]
     }
 
+    /// All bodies that take part in the simulation.
     pub fn bodies_iter(&mut self) -> impl Iterator<Item = &mut Body> {
-        self.bodies_vec.iter_mut()
+        self.bodies_vec.iter_mut().filter(|body| !body.is_disabled())
     }
 }
 
@@ -145,6 +145,9 @@ impl WiiMoteState {
             motion: motion_control,
             home_button_down: self.wii_mote.is_button_down(Button::Home),
             one_button_down: self.wii_mote.is_button_down(Button::One),
+            two_button_down: self.wii_mote.is_button_down(Button::Two),
+            plus_button_down: self.wii_mote.is_button_down(Button::Plus),
+            a_button_down: self.wii_mote.is_button_down(Button::A),
         };
     }
 }
@@ -13,24 +13,38 @@
 extern crate alloc;
 
 mod audio;
+// Only needed to read the Wii Remotes, which a replay does not do.
+#[cfg(not(feature = "replay"))]
 mod change_provider;
+#[cfg(not(feature = "replay"))]
 mod input;
+mod physics;
 mod rendering;
 mod target_tests;
 
 use audio::ogg_server::WiiOGGServer;
+#[cfg(not(feature = "replay"))]
 use change_provider::WiiChangeProvider;
 use core::sync::atomic::{AtomicBool, Ordering};
+#[cfg(feature = "replay")]
+use gamelib::game_state::changes::replay::ReplayChangeProvider;
+#[cfg(not(feature = "replay"))]
+use gamelib::game_state::changes::replay::RecordingChangeProvider;
 use gamelib::{
     data_store::asset_name::AssetName,
     game::Game,
+    game_state::changes::ChangeProvider,
     game_states::GameStateName,
-    servers::{audio::AudioServer, renderer::RenderServer, ServerProvider},
+    servers::{audio::AudioServer, physics::PhysicsServer, renderer::RenderServer, ServerProvider},
 };
 use grrustlib::{STM_ShutdownToStandby, SYS_SetPowerCallback};
+#[cfg(not(feature = "replay"))]
 use input::InputManager;
 use libc::exit;
+#[cfg(not(feature = "replay"))]
+use ogc_rs::clock::Instant;
 use ogc_rs::prelude::*;
+use physics::physics_server::WiiPhysicsServer;
 use rendering::render_server::WiiRenderServer;
 
 /// Global flag to signal to the main game loop when the game should quit.
@@ -59,21 +73,41 @@ fn main_game() -> isize {
 
     let audio_server = WiiOGGServer::new(Asnd::init());
     let render_server = WiiRenderServer::new();
-    let server_provider = ServerProvider::new(audio_server, render_server);
+    let physics_server = WiiPhysicsServer::new();
+    let server_provider = ServerProvider::new(audio_server, render_server, physics_server);
 
-    let mut game = Game::new(
-        GameStateName::BouncingCubes,
-        WiiChangeProvider::new(InputManager::new()),
-        server_provider,
-    );
+    #[cfg(feature = "replay")]
+    {
+        // Plays back a recorded session instead of reading the Wii Remotes.
+        let change_provider = ReplayChangeProvider::new(include_bytes!("../replay.bin"))
+            .expect("replay.bin is not a valid replay");
+        let seed = change_provider.seed();
+        let game = run_game(Game::new(GameStateName::BouncingCubes, seed, change_provider, server_provider));
+        game.snapshot_to_logs();
+    }
+    #[cfg(not(feature = "replay"))]
+    {
+        // Every session plays out differently, the seed is recorded along with the input.
+        let seed = Instant::now().ticks;
+        println!("Game seed: {}", seed);
+        // All input is recorded, so a session can be replayed exactly on the host or in Dolphin.
+        let change_provider = RecordingChangeProvider::new(WiiChangeProvider::new(InputManager::new()), seed);
+        let game = run_game(Game::new(GameStateName::BouncingCubes, seed, change_provider, server_provider));
+        game.change_provider().to_logs();
+        game.snapshot_to_logs();
+    }
+    shutdown()
+}
 
+/// Runs the game until it quits by itself, or the Wii is being shut down.
+fn run_game<C: ChangeProvider>(mut game: Game<C>) -> Game<C> {
     while KEEP_RUNNING.load(Ordering::SeqCst) {
         let should_continue = game.update();
         if !should_continue {
             break;
         }
     }
-    shutdown()
+    game
 }
 
 /// Registers the power callback,
@@ -0,0 +1,138 @@
+use gamelib::game_state::changes::controls::Direction;
+use gamelib::game_state::components::game::PotatoKind;
+use gamelib::game_state::components::motion::{Position, Rotation};
+use gamelib::game_state::components::physics::SphereCollider;
+use gamelib::servers::physics::{movement_for_direction, PhysicsServer};
+use ogc_rs::prelude::Vec;
+use ogc_rs::println;
+use physicslib::{Connection, Joint, Vec3, WorldWrapper};
+
+/// Height at which new bodies are created, before they are moved to their entity.
+const SPAWN_HEIGHT: f32 = 20.0;
+
+/// Physics simulation of the device, backed by tiny_physics.
+///
+/// Owns the physics world, so it can be swapped out and profiled independently of the renderer.
+pub struct WiiPhysicsServer {
+    world_wrapper: WorldWrapper,
+    sim_step: usize,
+}
+
+impl WiiPhysicsServer {
+    pub fn new() -> Self {
+        Self {
+            world_wrapper: WorldWrapper::new(),
+            sim_step: 0,
+        }
+    }
+}
+
+/**
+ * Implement the physics server for the game to use.
+ */
+impl PhysicsServer for WiiPhysicsServer {
+    fn register_collider(&mut self, colliders: &mut Vec<(&mut SphereCollider, Option<&PotatoKind>)>) {
+        // TODO: make this not happen every iteration
+        for (collider, kind) in colliders.iter_mut() {
+            if !collider.has_been_registered {
+                let kind = kind.copied().unwrap_or(PotatoKind::Regular);
+                let layout = kind.joints();
+                let joints: Vec<Joint> = layout
+                    .iter()
+                    .map(|((x, y, z), radius)| Joint::new(Vec3(*x, SPAWN_HEIGHT + *y, *z), *radius))
+                    .collect();
+                let mut connections = Vec::new();
+                for first in 0..layout.len() {
+                    for second in (first + 1)..layout.len() {
+                        connections.push(Connection::new(first as u8, second as u8, 0.5));
+                    }
+                }
+
+                collider.body_index = self.world_wrapper.add_body(joints, connections, kind.mass());
+                let body = self.world_wrapper.get_body(collider.body_index);
+                body.move_by(Vec3(
+                    collider.body_index as f32 * 0.5,
+                    collider.body_index as f32 * 0.5,
+                    collider.body_index as f32 * 0.5,
+                ));
+                collider.has_been_registered = true;
+            }
+        }
+    }
+
+    fn world_step(&mut self) {
+        const step_scale_velocity: usize = 1;
+        for body in self.world_wrapper.bodies_iter() {
+            let plate_height = 0.0;
+            let pos = body.center_of_mass();
+            let distance_from_center = pos.0 * pos.0 + pos.2 * pos.2;
+            let max_dis_from_center = 11.0;
+            if distance_from_center < max_dis_from_center * max_dis_from_center
+                && body.center_of_mass().1 < plate_height
+                && body.center_of_mass().1 > plate_height - 2.0
+            {
+                let mut pos = body.center_of_mass();
+                pos.1 = plate_height;
+                // TODO Add friction. using velocity
+                body.move_to(pos);
+                // println!("{:?}", self.world_wrapper.sim_step);
+                // Only scale velocity every n sim_steps
+                if self.sim_step >= step_scale_velocity {
+                    body.scale_velocity(0.99);
+                }
+            } else {
+                body.apply_gravity(1.0 / 100.0);
+            }
+        }
+
+        if self.sim_step >= step_scale_velocity {
+            self.sim_step = 0;
+        } else {
+            self.sim_step += 1;
+        }
+        self.world_wrapper.step();
+    }
+
+    fn physics_to_position(
+        &mut self,
+        objs: &mut Vec<(&mut SphereCollider, &mut Position, &mut Rotation)>,
+    ) {
+        for (col, pos, rot) in objs.iter_mut() {
+            let body = self.world_wrapper.get_body(col.body_index);
+            let center_of_mass = body.center_of_mass();
+            pos.x = center_of_mass.0;
+            pos.y = center_of_mass.1;
+            pos.z = center_of_mass.2;
+
+            let rotation = body.rotation();
+
+            rot.x = rotation.0;
+            rot.y = rotation.1;
+            rot.z = rotation.2;
+        }
+    }
+
+    fn apply_movement(&mut self, obj: &SphereCollider, dir: Direction) {
+        let body = self.world_wrapper.get_body(obj.body_index);
+        let (x, y, z) = movement_for_direction(dir);
+        body.accelerate(Vec3(x, y, z));
+    }
+
+    fn reset_world(&mut self) {
+        self.world_wrapper = WorldWrapper::new();
+    }
+
+    fn move_body_to(&mut self, obj: &SphereCollider, position: &Position) {
+        let body = self.world_wrapper.get_body(obj.body_index);
+        body.move_to(Vec3(position.x, position.y, position.z));
+        body.scale_velocity(0.0);
+    }
+
+    fn remove_body(&mut self, obj: &SphereCollider) {
+        self.world_wrapper.remove_body(obj.body_index);
+    }
+
+    fn body_count(&self) -> usize {
+        self.world_wrapper.body_count()
+    }
+}
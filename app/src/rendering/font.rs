@@ -0,0 +1,62 @@
+use alloc::vec::Vec;
+use grrustlib::{GRRLIB_InitTileSet, GRRLIB_LoadTexture, GRRLIB_Printf, GRRLIB_texImg};
+use libc::c_char;
+
+/// Width and height in pixels of a single character in the font tile set.
+const TILE_SIZE: u32 = 8;
+/// The first character in the font tile set, everything before it is not printable.
+const FIRST_CHARACTER: u32 = 32;
+
+/**
+ * A bitmap font, used to draw text on top of the 3D scene.
+ */
+#[derive(Debug)]
+pub struct Font {
+    grrlib_texture: *mut GRRLIB_texImg,
+}
+
+impl Font {
+    /**
+     * Load a font based on PNG image data, which contains 8x8 pixel tiles for the characters 32 up to 127.
+     */
+    pub fn from_bytes(png_data: impl AsRef<[u8]>) -> Result<Font, &'static str> {
+        unsafe {
+            let grrlib_texture = GRRLIB_LoadTexture(png_data.as_ref().as_ptr());
+            if grrlib_texture.is_null() {
+                return Err("Font could not be loaded");
+            }
+            GRRLIB_InitTileSet(grrlib_texture, TILE_SIZE, TILE_SIZE, FIRST_CHARACTER);
+            return Ok(Font { grrlib_texture });
+        }
+    }
+
+    /**
+     * The width in pixels of the text when it is printed at the given size.
+     * Every character is a tile, scaled up the same as in `print`.
+     */
+    pub fn width(&self, text: &str, size: f32) -> f32 {
+        let characters = text.bytes().filter(|byte| *byte != 0).count();
+        characters as f32 * TILE_SIZE as f32 * (size / TILE_SIZE as f32)
+    }
+
+    /**
+     * Print the text with its top left corner at the given screen position.
+     * Expects GRRLIB to be in 2D mode.
+     */
+    pub fn print(&self, text: &str, x: f32, y: f32, size: f32, rgba: u32) {
+        // GRRLIB_Printf formats its text, so pass ours as an argument to not have '%' interpreted.
+        let mut c_text: Vec<u8> = text.bytes().filter(|byte| *byte != 0).collect();
+        c_text.push(0);
+        unsafe {
+            GRRLIB_Printf(
+                x,
+                y,
+                self.grrlib_texture,
+                rgba,
+                size / TILE_SIZE as f32,
+                b"%s\0".as_ptr() as *const c_char,
+                c_text.as_ptr() as *const c_char,
+            );
+        }
+    }
+}
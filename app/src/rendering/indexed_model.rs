@@ -1,5 +1,6 @@
 use alloc::collections::BTreeMap;
 use alloc::vec::Vec;
+use gamelib::servers::culling::BoundingSphere;
 use ogc_rs::{print, println};
 use wavefront::{Index, Obj, Vertex};
 
@@ -10,12 +11,18 @@ use wavefront::{Index, Obj, Vertex};
 pub struct IndexedModel {
     pub positions: Vec<f32>,
     pub position_indices: Vec<u16>,
+    pub normals: Vec<f32>,
+    pub normal_indices: Vec<u16>,
     pub tex_coords: Vec<f32>,
     pub tex_coord_indices: Vec<u16>,
+    /// Holds all positions, to tell whether the camera can see an instance of the model.
+    pub bounds: BoundingSphere,
 }
 
 pub const SIZE_POSITION: usize = 3;
 pub const BYTE_SIZE_POSITION: usize = core::mem::size_of::<f32>() * SIZE_POSITION;
+pub const SIZE_NORMAL: usize = 3;
+pub const BYTE_SIZE_NORMAL: usize = core::mem::size_of::<f32>() * SIZE_NORMAL;
 pub const SIZE_TEX_COORD: usize = 2;
 pub const BYTE_SIZE_TEX_COORD: usize = core::mem::size_of::<f32>() * SIZE_TEX_COORD;
 
@@ -30,12 +37,17 @@ impl IndexedModel {
      */
     pub fn new(obj_data: &Obj) -> IndexedModel {
         let (positions, position_indices) = Self::index_positions(obj_data);
+        let (normals, normal_indices) = Self::index_normals(obj_data);
         let (tex_coords, tex_coord_indices) = Self::index_tex_coords(obj_data);
+        let bounds = BoundingSphere::from_positions(&positions);
         IndexedModel {
             positions,
             position_indices,
+            normals,
+            normal_indices,
             tex_coords,
             tex_coord_indices,
+            bounds,
         }
     }
 
@@ -59,6 +71,27 @@ impl IndexedModel {
         return (positions, position_indices);
     }
 
+    /**
+     * Generated indexed normal data from the given wavefront object.
+     * Vertices without a normal point straight up.
+     */
+    fn index_normals(obj_data: &Obj) -> (Vec<f32>, Vec<u16>) {
+        let mut normal_memo: BTreeMap<Index, u16> = BTreeMap::new();
+        let mut normals: Vec<f32> = Vec::new();
+        let normal_indices = obj_data
+            .vertices()
+            .map(|vertex| {
+                let normal_id = vertex.normal_index().unwrap_or(0usize);
+                *normal_memo.entry(normal_id).or_insert_with(|| {
+                    let index = u16::try_from(normals.len() / SIZE_NORMAL).unwrap();
+                    normals.extend(vertex.normal().unwrap_or([0.0, 1.0, 0.0]));
+                    index
+                })
+            })
+            .collect();
+        return (normals, normal_indices);
+    }
+
     /**
      * Generated indexed texture coordinate data from the given wavefront object.
      */
@@ -1,4 +1,5 @@
 pub mod display_cache;
+pub mod font;
 pub mod indexed_model;
 pub mod model_factory;
 pub mod render_server;
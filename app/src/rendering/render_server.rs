@@ -1,31 +1,24 @@
 use super::display_cache::DisplayCache;
-use super::indexed_model::{BYTE_SIZE_POSITION, BYTE_SIZE_TEX_COORD};
+use super::font::Font;
+use super::indexed_model::{BYTE_SIZE_NORMAL, BYTE_SIZE_POSITION, BYTE_SIZE_TEX_COORD};
 use super::model_factory::ModelFactory;
 use super::textured_model::TexturedModel;
-use alloc::vec;
+use alloc::collections::BTreeMap;
 use gamelib::data_store::asset_name::AssetName;
 use gamelib::data_store::textured_model_name::TexturedModelName;
-use gamelib::game_state::changes::controls::Direction;
 use gamelib::game_state::components::game::Camera;
-use gamelib::game_state::components::game::FryAssignment;
-use gamelib::game_state::components::motion::Rotation;
+use gamelib::game_state::components::light::Light;
+use gamelib::game_state::components::motion::{Rotation, Scale};
 use gamelib::game_state::components::physics::SphereCollider;
-use gamelib::game_state::components::render::MeshInstance;
-use gamelib::game_states::GameStateName;
-use gamelib::{
-    game_state::components::motion::Position, game_state::components::motion::Velocity,
-    game_state::GameState, servers::renderer::RenderServer,
-};
+use gamelib::game_state::components::render::{Colour, MeshInstance};
+use gamelib::servers::culling::Frustum;
+use gamelib::servers::renderer::{Billboard, RenderStats, SCREEN_HEIGHT, SCREEN_WIDTH};
+use gamelib::{game_state::components::motion::Position, servers::renderer::RenderServer};
 
 use grrustlib::*;
-use hecs::*;
 use libc::c_void;
 use ogc_rs::prelude::Vec;
 use ogc_rs::{print, println};
-use physicslib::{
-    Connection, Joint, TPE_Body, TPE_Joint, TPE_World, TPE_worldInit, Vec3, WorldWrapper,
-};
-use wavefront::{Obj, Vertex};
 
 /// Representation of the graphics rendering subsystem of the device
 ///
@@ -37,8 +30,41 @@ use wavefront::{Obj, Vertex};
 pub struct WiiRenderServer {
     model_factory: ModelFactory,
     display_cache: DisplayCache,
-    world_wrapper: WorldWrapper,
-    sim_step: usize,
+    font: Font,
+    /// The view matrix of the camera, to bring lights into eye space where GX lights the vertices.
+    view: Mtx,
+    /// Set when the meshes that are rendered next are lit, c.f. `update_lights`.
+    lighting: Option<Lighting>,
+    /// What the camera can see, once there is a camera.
+    frustum: Option<Frustum>,
+}
+
+/// The distance to the near and far plane of the camera.
+const NEAR: f32 = 0.1;
+const FAR: f32 = 1000.0;
+/// The vertical field of view of the camera, in degrees.
+const FIELD_OF_VIEW: f32 = 45.0;
+
+/// A mesh as it is handed to `render_meshes`.
+type MeshToRender<'a> = (&'a MeshInstance, &'a Position, &'a Rotation, &'a Scale);
+
+/// The number of lights GX can light a vertex with at once. Any further lights (apart from ambient ones) are left out.
+const MAX_LIGHTS: usize = 8;
+
+/// How far away a directional light is placed, so that it shines from the same direction on all meshes.
+const DIRECTIONAL_LIGHT_DISTANCE: f32 = 1.0e6;
+
+/// The most billboards in one GX batch: each takes 4 vertices and a batch holds at most `u16::MAX` of them.
+const MAX_BILLBOARDS_PER_BATCH: usize = u16::MAX as usize / 4;
+
+/**
+ * The lights that are loaded into GX for the meshes that are rendered next.
+ */
+struct Lighting {
+    /// Which of the GX lights are in use, as a mask of `GX_LIGHT0` and up.
+    mask: u8,
+    /// All ambient lights added up.
+    ambient: GXColor,
 }
 
 impl WiiRenderServer {
@@ -49,17 +75,13 @@ impl WiiRenderServer {
     /// - the graphics chip is initialized in the expected rendering mode.
     /// - The available models are constructed and indexed. (c.f. `ModelFactory`)
     pub fn new() -> Self {
-        let mut world_wrapper = WorldWrapper::new();
-        // world_wrapper.add_body(
-        //     vec![Joint::new(Vec3(0.0, -500.0, 0.0), 1000.0)],
-        //     vec![],
-        //     100000.0,
-        // );
         let res = Self {
             model_factory: ModelFactory::new(),
             display_cache: DisplayCache::new(),
-            world_wrapper,
-            sim_step: 0,
+            font: Font::from_bytes(AssetName::HudFont.to_data()).unwrap(),
+            view: [[1.0, 0.0, 0.0, 0.0], [0.0, 1.0, 0.0, 0.0], [0.0, 0.0, 1.0, 0.0]],
+            lighting: None,
+            frustum: None,
         };
         res.init_render();
         res
@@ -95,7 +117,114 @@ impl WiiRenderServer {
                 camera.lookat_y,
                 camera.lookat_z,
             );
+            let mut position = guVector { x: pos.x, y: pos.y, z: pos.z };
+            let mut up = guVector { x: camera.up_x, y: camera.up_y, z: camera.up_z };
+            let mut look_at = guVector { x: camera.lookat_x, y: camera.lookat_y, z: camera.lookat_z };
+            guLookAt(self.view.as_mut_ptr(), &mut position, &mut up, &mut look_at);
+        }
+        self.frustum = Some(Frustum::new(pos, camera, FIELD_OF_VIEW, SCREEN_WIDTH / SCREEN_HEIGHT, NEAR, FAR));
+    }
+
+    /// Whether the camera can see any part of the instance of the model.
+    fn is_visible(&self, model_name: &TexturedModelName, position: &Position, scale: &Scale) -> bool {
+        match (&self.frustum, self.model_factory.get_model(model_name)) {
+            (Some(frustum), Some(textured_model)) => frustum.intersects(&textured_model.model.bounds.around(position, scale)),
+            _ => true,
+        }
+    }
+
+    /// How far in front of the camera the position is: the further away, the more negative.
+    fn eye_depth(&self, position: &Position) -> f32 {
+        let row = &self.view[2];
+        row[0] * position.x + row[1] * position.y + row[2] * position.z + row[3]
+    }
+
+    /**
+     * Renders all instances of a single model.
+     * The mode, texture and vertex data are set up once, after which only the position and tint differ per instance.
+     */
+    fn render_bucket(&mut self, model_name: &TexturedModelName, instances: &[MeshToRender]) {
+        unsafe {
+            GRRLIB_3dMode(NEAR, FAR, FIELD_OF_VIEW, false, false);
+        }
+        self.prepare_textured_model(model_name);
+        for (mesh_instance, position, rotation, scale) in instances {
+            unsafe {
+                GRRLIB_ObjectView(
+                    position.x, position.y, position.z, rotation.x, rotation.y, rotation.z,
+                    scale.x, scale.y, scale.z,
+                );
+            }
+            self.set_tint(&mesh_instance.tint);
+            self.display_cache.get_display_list(model_name).set_active();
+        }
+        Self::set_tint_from_vertices();
+    }
+
+    /**
+     * Loads the lights into GX, in eye space, and remembers which of them the meshes are to be lit by.
+     * Ambient lights do not need a GX light of their own: they are added up into the ambient colour of the channel.
+     */
+    fn set_lights(&mut self, lights: Vec<(&Light, Option<&Position>)>) {
+        if lights.is_empty() {
+            self.lighting = None;
+            return;
+        }
+        let mut mask = 0u8;
+        let mut ambient = (0u32, 0u32, 0u32);
+        let mut light_count = 0;
+        for (light, position) in lights {
+            let colour = light.colour();
+            if let Light::Ambient { .. } = light {
+                ambient = (ambient.0 + colour.r as u32, ambient.1 + colour.g as u32, ambient.2 + colour.b as u32);
+                continue;
+            }
+            if light_count == MAX_LIGHTS {
+                continue;
+            }
+            unsafe {
+                let mut light_obj: GXLightObj = core::mem::zeroed();
+                GX_InitLightColor(&mut light_obj, GXColor { r: colour.r, g: colour.g, b: colour.b, a: 0xFF });
+                match *light {
+                    Light::Directional { direction, .. } => {
+                        // Light that comes from very far away only loses its brightness to the angle it hits the mesh at.
+                        let mut world = guVector { x: -direction.0, y: -direction.1, z: -direction.2 };
+                        let mut eye = guVector { x: 0.0, y: 0.0, z: 0.0 };
+                        c_guVecMultiplySR(self.view.as_mut_ptr(), &mut world, &mut eye);
+                        c_guVecNormalize(&mut eye);
+                        GX_InitLightPos(
+                            &mut light_obj,
+                            eye.x * DIRECTIONAL_LIGHT_DISTANCE,
+                            eye.y * DIRECTIONAL_LIGHT_DISTANCE,
+                            eye.z * DIRECTIONAL_LIGHT_DISTANCE,
+                        );
+                        GX_InitLightAttn(&mut light_obj, 1.0, 0.0, 0.0, 1.0, 0.0, 0.0);
+                    }
+                    Light::Point { radius, .. } => {
+                        let mut world = match position {
+                            Some(position) => guVector { x: position.x, y: position.y, z: position.z },
+                            None => guVector { x: 0.0, y: 0.0, z: 0.0 },
+                        };
+                        let mut eye = guVector { x: 0.0, y: 0.0, z: 0.0 };
+                        c_guVecMultiply(self.view.as_mut_ptr(), &mut world, &mut eye);
+                        GX_InitLightPos(&mut light_obj, eye.x, eye.y, eye.z);
+                        GX_InitLightSpot(&mut light_obj, 0.0, GX_SP_OFF as u8);
+                        GX_InitLightDistAttn(&mut light_obj, radius, 0.5, GX_DA_MEDIUM as u8);
+                    }
+                    Light::Ambient { .. } => {}
+                }
+                GX_LoadLightObj(&mut light_obj, (GX_LIGHT0 << light_count) as u8);
+            }
+            mask |= (GX_LIGHT0 << light_count) as u8;
+            light_count += 1;
         }
+        let ambient = GXColor {
+            r: ambient.0.min(0xFF) as u8,
+            g: ambient.1.min(0xFF) as u8,
+            b: ambient.2.min(0xFF) as u8,
+            a: 0xFF,
+        };
+        self.lighting = Some(Lighting { mask, ambient });
     }
 
     /// Render a single entity
@@ -104,14 +233,143 @@ impl WiiRenderServer {
         model_name: &TexturedModelName,
         position: &Position,
         rotation: &Rotation,
+        scale: &Scale,
+        tint: &Colour,
     ) {
         unsafe {
-            GRRLIB_3dMode(0.1, 1000.0, 45.0, false, false);
+            GRRLIB_3dMode(NEAR, FAR, FIELD_OF_VIEW, false, false);
             GRRLIB_ObjectView(
-                position.x, position.y, position.z, rotation.x, rotation.y, rotation.z, 1.0, 1.0,
-                1.0,
+                position.x, position.y, position.z, rotation.x, rotation.y, rotation.z, scale.x,
+                scale.y, scale.z,
             );
+            self.set_tint(tint);
             self.render_textured_model(model_name);
+            Self::set_tint_from_vertices();
+        }
+    }
+
+    /**
+     * Makes the colour channel use the tint as the colour of every vertex, which the texture is multiplied with.
+     * The tint is set through a register rather than per vertex, so the display list of a model can be shared by all of its instances.
+     * When there are lights, the tint is lit by them first.
+     */
+    fn set_tint(&self, tint: &Colour) {
+        let colour = GXColor { r: tint.r, g: tint.g, b: tint.b, a: tint.a };
+        unsafe {
+            match &self.lighting {
+                Some(lighting) => {
+                    GX_SetChanAmbColor(GX_COLOR0A0 as i32, lighting.ambient);
+                    GX_SetChanCtrl(
+                        GX_COLOR0A0 as i32,
+                        GX_ENABLE as u8,
+                        GX_SRC_REG as u8,
+                        GX_SRC_REG as u8,
+                        lighting.mask,
+                        GX_DF_CLAMP as u8,
+                        GX_AF_SPOT as u8,
+                    );
+                }
+                None => {
+                    GX_SetChanCtrl(
+                        GX_COLOR0A0 as i32,
+                        GX_DISABLE as u8,
+                        GX_SRC_REG as u8,
+                        GX_SRC_REG as u8,
+                        GX_LIGHTNULL as u8,
+                        GX_DF_NONE as u8,
+                        GX_AF_NONE as u8,
+                    );
+                }
+            }
+            GX_SetChanMatColor(GX_COLOR0A0 as i32, colour);
+        }
+    }
+
+    /**
+     * Makes the colour channel use the colour given with every vertex again, as the text and rectangles expect.
+     */
+    fn set_tint_from_vertices() {
+        unsafe {
+            GX_SetChanCtrl(
+                GX_COLOR0A0 as i32,
+                GX_DISABLE as u8,
+                GX_SRC_REG as u8,
+                GX_SRC_VTX as u8,
+                GX_LIGHTNULL as u8,
+                GX_DF_NONE as u8,
+                GX_AF_NONE as u8,
+            );
+        }
+    }
+
+    /**
+     * Draws an untextured, coloured rectangle in screen space.
+     */
+    fn render_rectangle(x: f32, y: f32, width: f32, height: f32, rgba: u32) {
+        unsafe {
+            GRRLIB_2dMode();
+            GX_SetTevOp(GX_TEVSTAGE0 as u8, GX_PASSCLR as u8);
+            GX_ClearVtxDesc();
+            GX_SetVtxDesc(GX_VA_POS as u8, GX_DIRECT as u8);
+            GX_SetVtxDesc(GX_VA_CLR0 as u8, GX_DIRECT as u8);
+            GX_SetVtxAttrFmt(GX_VTXFMT0 as u8, GX_VA_POS, GX_POS_XYZ, GX_F32, 0);
+            GX_SetVtxAttrFmt(GX_VTXFMT0 as u8, GX_VA_CLR0, GX_CLR_RGBA, GX_RGBA8, 0);
+            GX_Begin(GX_QUADS as u8, GX_VTXFMT0 as u8, 4);
+            GX_Position3f32(x, y, 0.0);
+            GX_Color1u32(rgba);
+            GX_Position3f32(x + width, y, 0.0);
+            GX_Color1u32(rgba);
+            GX_Position3f32(x + width, y + height, 0.0);
+            GX_Color1u32(rgba);
+            GX_Position3f32(x, y + height, 0.0);
+            GX_Color1u32(rgba);
+            GX_End();
+            GX_SetTevOp(GX_TEVSTAGE0 as u8, GX_MODULATE as u8);
+        }
+    }
+
+    /**
+     * Draws all billboards as batches of untextured, coloured quads.
+     * The quads are spanned by the right and up direction of the camera, which are the first two rows of the view matrix,
+     * so they face the camera without a matrix per billboard.
+     * They are hidden behind meshes in front of them, but do not hide each other, so the order they are drawn in does not matter.
+     */
+    fn render_billboard_batch(&self, billboards: &[Billboard]) {
+        if billboards.is_empty() {
+            return;
+        }
+        let right = (self.view[0][0], self.view[0][1], self.view[0][2]);
+        let up = (self.view[1][0], self.view[1][1], self.view[1][2]);
+        unsafe {
+            GRRLIB_3dMode(NEAR, FAR, FIELD_OF_VIEW, false, false);
+            GRRLIB_ObjectView(0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 1.0, 1.0, 1.0);
+            Self::set_tint_from_vertices();
+            GX_SetTevOp(GX_TEVSTAGE0 as u8, GX_PASSCLR as u8);
+            GX_ClearVtxDesc();
+            GX_SetVtxDesc(GX_VA_POS as u8, GX_DIRECT as u8);
+            GX_SetVtxDesc(GX_VA_CLR0 as u8, GX_DIRECT as u8);
+            GX_SetVtxAttrFmt(GX_VTXFMT0 as u8, GX_VA_POS, GX_POS_XYZ, GX_F32, 0);
+            GX_SetVtxAttrFmt(GX_VTXFMT0 as u8, GX_VA_CLR0, GX_CLR_RGBA, GX_RGBA8, 0);
+            GX_SetZMode(GX_TRUE as u8, GX_LEQUAL as u8, GX_FALSE as u8);
+            for chunk in billboards.chunks(MAX_BILLBOARDS_PER_BATCH) {
+                GX_Begin(GX_QUADS as u8, GX_VTXFMT0 as u8, (chunk.len() * 4) as u16);
+                for billboard in chunk {
+                    let half = billboard.size / 2.0;
+                    let (x, y, z) = billboard.position;
+                    let rgba = billboard.colour.to_rgba();
+                    for (side, height) in [(-1.0, 1.0), (1.0, 1.0), (1.0, -1.0), (-1.0, -1.0)] {
+                        GX_Position3f32(
+                            x + (right.0 * side + up.0 * height) * half,
+                            y + (right.1 * side + up.1 * height) * half,
+                            z + (right.2 * side + up.2 * height) * half,
+                        );
+                        GX_Color1u32(rgba);
+                    }
+                }
+                GX_End();
+            }
+            GX_SetZMode(GX_TRUE as u8, GX_LEQUAL as u8, GX_TRUE as u8);
+            GX_SetTevOp(GX_TEVSTAGE0 as u8, GX_MODULATE as u8);
         }
     }
 
@@ -119,6 +377,15 @@ impl WiiRenderServer {
      * Renders the given model at whatever position was set previously using other calls into GRRLIB / GX.
      */
     fn render_textured_model(&mut self, model_name: &TexturedModelName) {
+        self.prepare_textured_model(model_name);
+        self.display_cache.get_display_list(model_name).set_active();
+    }
+
+    /**
+     * Sets the texture and vertex data of the given model, and records its display list if that did not happen yet.
+     * After this, every call to the display list draws the model.
+     */
+    fn prepare_textured_model(&mut self, model_name: &TexturedModelName) {
         let textured_model = self.model_factory.get_model(model_name).unwrap();
         textured_model.texture.set_active(true);
         Self::pass_textured_model_data(textured_model);
@@ -130,18 +397,20 @@ impl WiiRenderServer {
             Self::pass_textured_model_data_indices(textured_model);
             display_list.close();
         }
-        display_list.set_active();
     }
 
     /**
      * Describe the data format we push to the GPU as indexed data.
+     * There is no colour per vertex, the tint of the instance is used instead (c.f. `set_tint`).
      */
     fn pass_textured_model_description() {
         unsafe {
             GX_SetVtxDesc(GX_VA_POS as u8, GX_INDEX16 as u8);
-            GX_SetVtxDesc(GX_VA_CLR0 as u8, GX_DIRECT as u8);
+            GX_SetVtxDesc(GX_VA_NRM as u8, GX_INDEX16 as u8);
+            GX_SetVtxDesc(GX_VA_CLR0 as u8, GX_NONE as u8);
             GX_SetVtxDesc(GX_VA_TEX0 as u8, GX_INDEX16 as u8);
             GX_SetVtxAttrFmt(GX_VTXFMT0 as u8, GX_VA_POS, GX_POS_XYZ, GX_F32, 0);
+            GX_SetVtxAttrFmt(GX_VTXFMT0 as u8, GX_VA_NRM, GX_NRM_XYZ, GX_F32, 0);
             GX_SetVtxAttrFmt(GX_VTXFMT0 as u8, GX_VA_TEX0, GX_TEX_ST, GX_F32, 0);
         }
     }
@@ -157,9 +426,11 @@ impl WiiRenderServer {
      */
     fn pass_textured_model_data(textured_model: &TexturedModel) {
         let positions_ptr = textured_model.model.positions.as_ptr().cast_mut() as *mut c_void;
+        let normals_ptr = textured_model.model.normals.as_ptr().cast_mut() as *mut c_void;
         let tex_coord_ptr = textured_model.model.tex_coords.as_ptr().cast_mut() as *mut c_void;
         unsafe {
             GX_SetArray(GX_VA_POS, positions_ptr, BYTE_SIZE_POSITION as u8);
+            GX_SetArray(GX_VA_NRM, normals_ptr, BYTE_SIZE_NORMAL as u8);
             GX_SetArray(GX_VA_TEX0, tex_coord_ptr, BYTE_SIZE_TEX_COORD as u8);
         }
     }
@@ -178,10 +449,11 @@ impl WiiRenderServer {
             );
             let vertex_count = textured_model.model.position_indices.len();
             let position_indices = textured_model.model.position_indices.to_vec();
+            let normal_indices = textured_model.model.normal_indices.to_vec();
             let tex_coord_indices = textured_model.model.tex_coord_indices.to_vec();
             for index in 0..vertex_count {
                 GX_Position1x16(position_indices[index]);
-                GX_Color1u32(0xFFFFFFFF);
+                GX_Normal1x16(normal_indices[index]);
                 GX_TexCoord1x16(tex_coord_indices[index]);
             }
             GX_End();
@@ -204,18 +476,44 @@ impl Drop for WiiRenderServer {
  */
 impl RenderServer for WiiRenderServer {
     /*
-     * Render all given meshes.
-     * As part of this, refreshes the graphics buffer and wait for the next frame.
+     * Render all given meshes, one model at a time.
+     * Meshes outside of the view of the camera are skipped.
      */
-    fn render_meshes(&mut self, meshes: Vec<(&MeshInstance, &Position, &Rotation)>) {
-        for (mesh_instance, position, rotation) in meshes {
-            self.render_entity(&mesh_instance.model_name, position, rotation);
+    fn render_meshes(&mut self, meshes: Vec<(&MeshInstance, &Position, &Rotation, &Scale)>) -> RenderStats {
+        let mut stats = RenderStats::default();
+        let mut buckets: BTreeMap<TexturedModelName, Vec<MeshToRender>> = BTreeMap::new();
+        let mut translucent: Vec<MeshToRender> = Vec::new();
+        for mesh in meshes {
+            let (mesh_instance, position, _rotation, scale) = mesh;
+            if !self.is_visible(&mesh_instance.model_name, position, scale) {
+                stats.culled += 1;
+                continue;
+            }
+            if mesh_instance.tint.a < 0xFF {
+                translucent.push(mesh);
+            } else {
+                buckets.entry(mesh_instance.model_name.clone()).or_insert_with(Vec::new).push(mesh);
+            }
+            stats.drawn += 1;
+        }
+        for (model_name, instances) in buckets.iter() {
+            self.render_bucket(model_name, instances);
+        }
+        // See-through meshes blend with whatever is behind them, so they come last, from back to front.
+        translucent.sort_by(|a, b| self.eye_depth(a.1).total_cmp(&self.eye_depth(b.1)));
+        for mesh in translucent.iter() {
+            self.render_bucket(&mesh.0.model_name, core::slice::from_ref(mesh));
         }
+        stats
+    }
+
+    fn update_lights(&mut self, lights: Vec<(&Light, Option<&Position>)>) {
+        self.set_lights(lights)
     }
 
     fn render_debug(&mut self, data: Vec<(&Position, &SphereCollider, &Rotation)>) {
         for (pos, collider, rot) in data {
-            self.render_entity(&TexturedModelName::Cube, pos, rot);
+            self.render_entity(&TexturedModelName::Cube, pos, rot, &Scale::ONE, &Colour::WHITE);
         }
     }
 
@@ -223,190 +521,33 @@ impl RenderServer for WiiRenderServer {
         self.set_camera(camera, pos)
     }
 
-    /**
-     * Render a new frame.
-     */
-    fn render_frame(&mut self) {
+    fn render_text(&mut self, text: &str, x: f32, y: f32, size: f32, colour: &Colour) {
         unsafe {
-            GRRLIB_Render();
+            GRRLIB_2dMode();
         }
+        self.font.print(text, x, y, size, colour.to_rgba());
     }
 
-    fn register_collider(&mut self, colliders: &mut Vec<&mut SphereCollider>) {
-        // TODO: make this not happen every iteration
-        for collider in colliders.iter_mut() {
-            if !collider.has_been_registered {
-                /// POOTAATOO
-                let joints = vec![
-                    Joint::new(Vec3(0.0, 19.7, 0.0), 0.4),
-                    Joint::new(Vec3(0.0, 20.0, 0.0), 1.0),
-                    Joint::new(Vec3(0.0, 20.3, 0.0), 0.5),
-                ];
-                let connections = vec![
-                    Connection::new(0, 1, 0.5),
-                    Connection::new(0, 2, 0.5),
-                    Connection::new(1, 2, 0.5),
-                ];
-
-                collider.body_index = self.world_wrapper.add_body(joints, connections, 10.0);
-                let body = self.world_wrapper.get_body(collider.body_index);
-                body.move_by(Vec3(
-                    collider.body_index as f32 * 0.5,
-                    collider.body_index as f32 * 0.5,
-                    collider.body_index as f32 * 0.5,
-                ));
-                collider.has_been_registered = true;
-            }
-        }
+    fn text_width(&self, text: &str, size: f32) -> f32 {
+        self.font.width(text, size)
     }
 
-    fn world_step(&mut self) {
-        const step_scale_velocity: usize = 1;
-        for body in self.world_wrapper.bodies_iter() {
-            let plate_height = 0.0;
-            let pos = body.center_of_mass();
-            let distance_from_center = pos.0 * pos.0 + pos.2 * pos.2;
-            let max_dis_from_center = 11.0;
-            if distance_from_center < max_dis_from_center * max_dis_from_center
-                && body.center_of_mass().1 < plate_height
-                && body.center_of_mass().1 > plate_height - 2.0
-            {
-                let mut pos = body.center_of_mass();
-                pos.1 = plate_height;
-                // TODO Add friction. using velocity
-                body.move_to(pos);
-                // println!("{:?}", self.world_wrapper.sim_step);
-                // Only scale velocity every n sim_steps
-                if self.sim_step >= step_scale_velocity {
-                    body.scale_velocity(0.99);
-                }
-            } else {
-                body.apply_gravity(1.0 / 100.0);
-                if body.center_of_mass().1 < plate_height - 20.0 {
-                    body.move_to(Vec3 {
-                        0: 0.0,
-                        1: 10.0,
-                        2: 0.0,
-                    });
-                    body.scale_velocity(0.0);
-                }
-            }
-        }
-
-        if self.sim_step >= step_scale_velocity {
-            self.sim_step = 0;
-        } else {
-            self.sim_step += 1;
-        }
-        self.world_wrapper.step();
+    fn render_quad(&mut self, x: f32, y: f32, width: f32, height: f32, colour: &Colour) {
+        Self::render_rectangle(x, y, width, height, colour.to_rgba());
     }
 
-    fn physics_to_position(
-        &mut self,
-        objs: &mut Vec<(&mut SphereCollider, &mut Position, &mut Rotation)>,
-    ) {
-        for (col, pos, rot) in objs.iter_mut() {
-            let body = self.world_wrapper.get_body(col.body_index);
-            let center_of_mass = body.center_of_mass();
-            pos.x = center_of_mass.0;
-            pos.y = center_of_mass.1;
-            pos.z = center_of_mass.2;
-
-            let rotation = body.rotation();
-
-            rot.x = rotation.0;
-            rot.y = rotation.1;
-            rot.z = rotation.2;
-        }
+    fn render_billboards(&mut self, billboards: Vec<Billboard>) {
+        self.render_billboard_batch(&billboards);
     }
 
-    fn apply_movement(&mut self, obj: &SphereCollider, dir: Direction) {
-        let body = self.world_wrapper.get_body(obj.body_index);
-        let move_magnitude = 0.1;
-        let move_help_jump_magnitude = 0.1;
-        let jump_magnitude = 0.3;
-        let jump_down_magnitude = 0.2;
-        let rotation = match dir {
-            Direction::Xp => Vec3 {
-                0: -move_magnitude,
-                1: move_help_jump_magnitude,
-                2: 0.0,
-            },
-            Direction::Xn => Vec3 {
-                0: move_magnitude,
-                1: move_help_jump_magnitude,
-                2: 0.0,
-            },
-            Direction::Yp | Direction::Zp => Vec3 {
-                0: 0.0,
-                1: move_help_jump_magnitude,
-                2: move_magnitude,
-            },
-            Direction::Yn | Direction::Zn => Vec3 {
-                0: 0.0,
-                1: move_help_jump_magnitude,
-                2: -move_magnitude,
-            },
-            // Direction::Zp => Vec3 {
-            //     0: 0.0,
-            //     1: jump_magnitude,
-            //     2: 0.0,
-            // },
-            // Direction::Zn => Vec3 {
-            //     0: 0.0,
-            //     1: -jump_down_magnitude,
-            //     2: 0.0,
-            // },
-        };
-        body.accelerate(rotation);
-    }
-
-    fn reset_world(&mut self) {
-        self.world_wrapper = WorldWrapper::new();
-    }
-
-    fn teleport_potato(
-        &mut self,
-        objs: &mut Vec<(&mut SphereCollider, &mut Position, &mut Rotation)>,
-    ) {
-        for (potato, _a, _b) in objs {
-            let body = self.world_wrapper.get_body(potato.body_index);
-            if body.center_of_mass().1 < -20.0 {
-                body.move_to(Vec3 {
-                    0: 0.0,
-                    1: 10.0,
-                    2: 0.0,
-                });
-                body.scale_velocity(0.0);
-            }
-        }
-    }
-
-    fn fry_pan_score_increase(
-        &mut self,
-        position: &mut Position,
-        fry_assignment: &mut FryAssignment,
-        potatoes: &Vec<usize>,
-    ) {
-        let fry_pan_radius = 10.0;
-        for potato_body_index in potatoes {
-            let body = self.world_wrapper.get_body(*potato_body_index);
-            if body.center_of_mass().1 < -20.0 {
-                // println!(
-                //     "potato x:{:?} z:{:?}",
-                //     body.center_of_mass().0,
-                //     body.center_of_mass().2
-                // );
-                // println!("x:{:?} z:{:?}", position.x, position.z);
-                let x_dif = body.center_of_mass().0 - position.x;
-                let z_dif = body.center_of_mass().2 - position.z;
-                // let distance = x_dif * x_dif + z_dif * z_dif
-                // println!(distance);
-                if x_dif * x_dif + z_dif * z_dif < fry_pan_radius * fry_pan_radius {
-                    fry_assignment.score += 1;
-                    println!("SCOREEE");
-                }
-            }
+    /**
+     * Render a new frame.
+     * The lights are forgotten afterwards, so a state that does not submit any lights is not lit by those of the last one.
+     */
+    fn render_frame(&mut self) {
+        unsafe {
+            GRRLIB_Render();
         }
+        self.lighting = None;
     }
 }
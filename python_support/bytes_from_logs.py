@@ -0,0 +1,47 @@
+import sys
+from pathlib import Path
+
+# Extracts the last block of bytes with the given marker (between START_<marker> and STOP_<marker>)
+# found in the provided logs file into a binary file.
+# Use REPLAY for replays (played back with the `ReplayChangeProvider`)
+# and SNAPSHOT for game state snapshots (restored with `snapshot::restore`).
+def parse_log_line(line: str):
+    s = line.split('| ')
+    if len(s) > 1:
+        return s[1].replace('\n', '')
+    return ''
+
+
+def main():
+    if not len(sys.argv) == 4:
+        print("run using: bytes_from_logs.py <REPLAY|SNAPSHOT> <path_to_log_file> <path_to_output_file>")
+        exit()
+    marker = sys.argv[1]
+    log_file = Path(sys.argv[2])
+    output_file = Path(sys.argv[3])
+
+    start_block = 0
+    stop_block = 0
+    with open(log_file, 'r') as f:
+        file_content = f.readlines()
+
+    for i, line in enumerate(file_content):
+        parsed = parse_log_line(line)
+        if parsed == f'START_{marker}':
+            start_block = i
+        elif parsed == f'STOP_{marker}':
+            stop_block = i
+
+    block = bytearray()
+    for line_i in range(start_block + 1, stop_block):
+        parsed = parse_log_line(file_content[line_i])
+        if parsed.startswith('H_'):
+            block += bytes.fromhex(parsed[2:])
+
+    with open(output_file, 'wb') as f:
+        f.write(block)
+    print(f"Wrote {len(block)} bytes to {output_file}")
+
+
+if __name__ == '__main__':
+    main()
@@ -0,0 +1,115 @@
+import struct
+import sys
+import zlib
+from pathlib import Path
+
+# Generates the bitmap font used for the HUD, as a PNG tile set for GRRLIB_Printf:
+# 8x8 pixel tiles, 16 per row, for the ASCII characters 32 up to 127.
+# Glyphs are white on a transparent background, so they can be drawn in any colour.
+# Lowercase letters are drawn the same as uppercase ones.
+GLYPHS = {
+    '0': [" ### ", "#   #", "#  ##", "# # #", "##  #", "#   #", " ### "],
+    '1': ["  #  ", " ##  ", "  #  ", "  #  ", "  #  ", "  #  ", " ### "],
+    '2': [" ### ", "#   #", "    #", "   # ", "  #  ", " #   ", "#####"],
+    '3': ["#####", "   # ", "  #  ", "   # ", "    #", "#   #", " ### "],
+    '4': ["   # ", "  ## ", " # # ", "#  # ", "#####", "   # ", "   # "],
+    '5': ["#####", "#    ", "#### ", "    #", "    #", "#   #", " ### "],
+    '6': ["  ## ", " #   ", "#    ", "#### ", "#   #", "#   #", " ### "],
+    '7': ["#####", "    #", "   # ", "  #  ", " #   ", " #   ", " #   "],
+    '8': [" ### ", "#   #", "#   #", " ### ", "#   #", "#   #", " ### "],
+    '9': [" ### ", "#   #", "#   #", " ####", "    #", "   # ", " ##  "],
+    'A': [" ### ", "#   #", "#   #", "#####", "#   #", "#   #", "#   #"],
+    'B': ["#### ", "#   #", "#   #", "#### ", "#   #", "#   #", "#### "],
+    'C': [" ### ", "#   #", "#    ", "#    ", "#    ", "#   #", " ### "],
+    'D': ["#### ", "#   #", "#   #", "#   #", "#   #", "#   #", "#### "],
+    'E': ["#####", "#    ", "#    ", "#### ", "#    ", "#    ", "#####"],
+    'F': ["#####", "#    ", "#    ", "#### ", "#    ", "#    ", "#    "],
+    'G': [" ### ", "#   #", "#    ", "# ###", "#   #", "#   #", " ####"],
+    'H': ["#   #", "#   #", "#   #", "#####", "#   #", "#   #", "#   #"],
+    'I': [" ### ", "  #  ", "  #  ", "  #  ", "  #  ", "  #  ", " ### "],
+    'J': ["  ###", "   # ", "   # ", "   # ", "   # ", "#  # ", " ##  "],
+    'K': ["#   #", "#  # ", "# #  ", "##   ", "# #  ", "#  # ", "#   #"],
+    'L': ["#    ", "#    ", "#    ", "#    ", "#    ", "#    ", "#####"],
+    'M': ["#   #", "## ##", "# # #", "# # #", "#   #", "#   #", "#   #"],
+    'N': ["#   #", "#   #", "##  #", "# # #", "#  ##", "#   #", "#   #"],
+    'O': [" ### ", "#   #", "#   #", "#   #", "#   #", "#   #", " ### "],
+    'P': ["#### ", "#   #", "#   #", "#### ", "#    ", "#    ", "#    "],
+    'Q': [" ### ", "#   #", "#   #", "#   #", "# # #", "#  # ", " ## #"],
+    'R': ["#### ", "#   #", "#   #", "#### ", "# #  ", "#  # ", "#   #"],
+    'S': [" ####", "#    ", "#    ", " ### ", "    #", "    #", "#### "],
+    'T': ["#####", "  #  ", "  #  ", "  #  ", "  #  ", "  #  ", "  #  "],
+    'U': ["#   #", "#   #", "#   #", "#   #", "#   #", "#   #", " ### "],
+    'V': ["#   #", "#   #", "#   #", "#   #", "#   #", " # # ", "  #  "],
+    'W': ["#   #", "#   #", "#   #", "# # #", "# # #", "# # #", " # # "],
+    'X': ["#   #", "#   #", " # # ", "  #  ", " # # ", "#   #", "#   #"],
+    'Y': ["#   #", "#   #", " # # ", "  #  ", "  #  ", "  #  ", "  #  "],
+    'Z': ["#####", "    #", "   # ", "  #  ", " #   ", "#    ", "#####"],
+    ':': ["     ", "  #  ", "  #  ", "     ", "  #  ", "  #  ", "     "],
+    '/': ["     ", "    #", "   # ", "  #  ", " #   ", "#    ", "     "],
+    '-': ["     ", "     ", "     ", "#####", "     ", "     ", "     "],
+    '+': ["     ", "  #  ", "  #  ", "#####", "  #  ", "  #  ", "     "],
+    '=': ["     ", "     ", "#####", "     ", "#####", "     ", "     "],
+    '.': ["     ", "     ", "     ", "     ", "     ", " ##  ", " ##  "],
+    ',': ["     ", "     ", "     ", "     ", " ##  ", "  #  ", " #   "],
+    '!': ["  #  ", "  #  ", "  #  ", "  #  ", "  #  ", "     ", "  #  "],
+    '?': [" ### ", "#   #", "    #", "   # ", "  #  ", "     ", "  #  "],
+    "'": ["  #  ", "  #  ", " #   ", "     ", "     ", "     ", "     "],
+    '(': ["   # ", "  #  ", " #   ", " #   ", " #   ", "  #  ", "   # "],
+    ')': [" #   ", "  #  ", "   # ", "   # ", "   # ", "  #  ", " #   "],
+    '%': ["##   ", "##  #", "   # ", "  #  ", " #   ", "#  ##", "   ##"],
+}
+
+TILE_SIZE = 8
+TILES_PER_ROW = 16
+FIRST_CHARACTER = 32
+CHARACTER_COUNT = 96
+
+
+def glyph_for(character: str):
+    return GLYPHS.get(character.upper())
+
+
+def render_pixels():
+    rows = CHARACTER_COUNT // TILES_PER_ROW
+    width = TILES_PER_ROW * TILE_SIZE
+    height = rows * TILE_SIZE
+    pixels = [[(0, 0, 0, 0)] * width for _ in range(height)]
+    for index in range(CHARACTER_COUNT):
+        glyph = glyph_for(chr(FIRST_CHARACTER + index))
+        if glyph is None:
+            continue
+        tile_x = (index % TILES_PER_ROW) * TILE_SIZE
+        tile_y = (index // TILES_PER_ROW) * TILE_SIZE
+        for y, line in enumerate(glyph):
+            for x, pixel in enumerate(line):
+                if pixel == '#':
+                    # One pixel of padding on the left and top, so characters do not touch.
+                    pixels[tile_y + 1 + y][tile_x + 1 + x] = (255, 255, 255, 255)
+    return width, height, pixels
+
+
+def png_chunk(kind: bytes, data: bytes):
+    chunk = kind + data
+    return struct.pack('>I', len(data)) + chunk + struct.pack('>I', zlib.crc32(chunk) & 0xFFFFFFFF)
+
+
+def write_png(path: Path, width: int, height: int, pixels):
+    raw = b''.join(b'\x00' + b''.join(bytes(pixel) for pixel in row) for row in pixels)
+    header = struct.pack('>IIBBBBB', width, height, 8, 6, 0, 0, 0)
+    with open(path, 'wb') as f:
+        f.write(b'\x89PNG\r\n\x1a\n')
+        f.write(png_chunk(b'IHDR', header))
+        f.write(png_chunk(b'IDAT', zlib.compress(raw, 9)))
+        f.write(png_chunk(b'IEND', b''))
+
+
+def main():
+    if not len(sys.argv) == 2:
+        print("run using: generate_font.py <path_to_output_png>")
+        exit()
+    width, height, pixels = render_pixels()
+    write_png(Path(sys.argv[1]), width, height, pixels)
+
+
+if __name__ == "__main__":
+    main()